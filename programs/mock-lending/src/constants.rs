@@ -0,0 +1,19 @@
+// Constants for the Mock Lending program
+
+/// Seed for lending pool PDA
+pub const POOL_SEED: &[u8] = b"pool";
+
+/// Seed for the pool's reserve token account PDA
+pub const RESERVE_SEED: &[u8] = b"reserve";
+
+/// Seed for per-owner position PDA
+pub const POSITION_SEED: &[u8] = b"position";
+
+/// Basis points denominator (100% = 10000 bps)
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Seconds per 365-day year, used for simple interest
+pub const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+
+/// Maximum APY (10000%), high enough to see interest within a test run
+pub const MAX_APY_BPS: u32 = 1_000_000;
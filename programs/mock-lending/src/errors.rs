@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum LendingError {
+    #[msg("Unauthorized - only the pool authority can perform this action")]
+    Unauthorized,
+
+    #[msg("APY too high - maximum 1000000 bps")]
+    InvalidApy,
+
+    #[msg("Amount must be greater than zero")]
+    ZeroAmount,
+
+    #[msg("Amount exceeds the position value")]
+    InsufficientPosition,
+
+    #[msg("Pool reserves cannot cover the withdrawal")]
+    InsufficientReserves,
+
+    #[msg("Math overflow")]
+    MathOverflow,
+
+    #[msg("Every position of the pool must be passed once to accrue at the old APY")]
+    IncompletePositions,
+}
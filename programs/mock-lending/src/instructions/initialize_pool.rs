@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{constants::*, errors::*, state::*};
+
+/// Create a lending pool and its reserve for an asset mint
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    /// Pool authority and rent payer
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub asset_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = LendingPool::SPACE,
+        seeds = [POOL_SEED, asset_mint.key().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, LendingPool>,
+
+    /// Reserve token account, owned by the pool PDA
+    #[account(
+        init,
+        payer = authority,
+        seeds = [RESERVE_SEED, pool.key().as_ref()],
+        bump,
+        token::mint = asset_mint,
+        token::authority = pool,
+        token::token_program = token_program,
+    )]
+    pub reserve: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<InitializePool>, apy_bps: u32) -> Result<()> {
+    require!(apy_bps <= MAX_APY_BPS, LendingError::InvalidApy);
+
+    let pool = &mut ctx.accounts.pool;
+    pool.authority = ctx.accounts.authority.key();
+    pool.asset_mint = ctx.accounts.asset_mint.key();
+    pool.reserve = ctx.accounts.reserve.key();
+    pool.apy_bps = apy_bps;
+    pool.bump = ctx.bumps.pool;
+    pool.reserve_bump = ctx.bumps.reserve;
+    pool.position_count = 0;
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::*, errors::*, state::*};
+
+/// Write a position down by `amount`, simulating bad debt
+///
+/// The written-off tokens stay in the reserve.
+#[derive(Accounts)]
+pub struct InjectLoss<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(has_one = authority @ LendingError::Unauthorized)]
+    pub pool: Account<'info, LendingPool>,
+
+    #[account(
+        mut,
+        seeds = [POSITION_SEED, pool.key().as_ref(), position.owner.as_ref()],
+        bump = position.bump,
+    )]
+    pub position: Account<'info, Position>,
+}
+
+pub fn handler(ctx: Context<InjectLoss>, amount: u64) -> Result<()> {
+    let position = &mut ctx.accounts.position;
+    position.accrue(ctx.accounts.pool.apy_bps, Clock::get()?.unix_timestamp)?;
+    position.value = position
+        .value
+        .checked_sub(amount)
+        .ok_or(LendingError::InsufficientPosition)?;
+    Ok(())
+}
@@ -0,0 +1,17 @@
+#![allow(ambiguous_glob_reexports)]
+
+pub mod deposit;
+pub mod initialize_pool;
+pub mod inject_loss;
+pub mod open_position;
+pub mod set_apy;
+pub mod total_value;
+pub mod withdraw;
+
+pub use deposit::*;
+pub use initialize_pool::*;
+pub use inject_loss::*;
+pub use open_position::*;
+pub use set_apy::*;
+pub use total_value::*;
+pub use withdraw::*;
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::*, errors::*, state::*};
+
+/// Create an empty position for `owner`
+///
+/// Separate from `deposit` because a vault's owner is a PDA that cannot pay rent.
+#[derive(Accounts)]
+pub struct OpenPosition<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Any account may own a position; it must sign to move funds
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub pool: Account<'info, LendingPool>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = Position::SPACE,
+        seeds = [POSITION_SEED, pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<OpenPosition>) -> Result<()> {
+    let position = &mut ctx.accounts.position;
+    position.pool = ctx.accounts.pool.key();
+    position.owner = ctx.accounts.owner.key();
+    position.value = 0;
+    position.last_accrued_at = Clock::get()?.unix_timestamp;
+    position.bump = ctx.bumps.position;
+
+    let pool = &mut ctx.accounts.pool;
+    pool.position_count = pool
+        .position_count
+        .checked_add(1)
+        .ok_or(LendingError::MathOverflow)?;
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::*, errors::*, state::*};
+
+/// Change the pool APY
+///
+/// Remaining accounts: every position of the pool, writable. Positions accrue
+/// lazily, so each is accrued at the old rate up to now before it changes.
+#[derive(Accounts)]
+pub struct SetApy<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority @ LendingError::Unauthorized,
+    )]
+    pub pool: Account<'info, LendingPool>,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SetApy<'info>>,
+    apy_bps: u32,
+) -> Result<()> {
+    require!(apy_bps <= MAX_APY_BPS, LendingError::InvalidApy);
+
+    // Distinct positions of this pool, as many as were opened, are all of them
+    let pool = &mut ctx.accounts.pool;
+    require!(
+        ctx.remaining_accounts.len() == pool.position_count as usize,
+        LendingError::IncompletePositions
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    for (i, info) in ctx.remaining_accounts.iter().enumerate() {
+        let mut position: Account<'info, Position> = Account::try_from(info)?;
+        require!(
+            position.pool == pool.key()
+                && !ctx.remaining_accounts[..i]
+                    .iter()
+                    .any(|other| other.key == info.key),
+            LendingError::IncompletePositions
+        );
+        position.accrue(pool.apy_bps, now)?;
+        position.exit(ctx.program_id)?;
+    }
+
+    pool.apy_bps = apy_bps;
+    Ok(())
+}
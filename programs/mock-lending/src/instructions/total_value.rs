@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenInterface};
+
+use crate::{constants::*, state::*};
+
+/// Adapter `total_value`: current position value including accrued interest
+///
+/// Account order follows the vault's strategy adapter interface. Read-only.
+#[derive(Accounts)]
+pub struct TotalValue<'info> {
+    /// CHECK: Identifies the position through its seeds; need not sign
+    pub owner: UncheckedAccount<'info>,
+
+    /// CHECK: Part of the interface prefix, unused here
+    pub owner_token_account: UncheckedAccount<'info>,
+
+    pub asset_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(has_one = asset_mint)]
+    pub pool: Account<'info, LendingPool>,
+
+    /// CHECK: Part of the shared adapter accounts, unused here
+    pub reserve: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [POSITION_SEED, pool.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump,
+    )]
+    pub position: Account<'info, Position>,
+}
+
+pub fn handler(ctx: Context<TotalValue>) -> Result<u64> {
+    ctx.accounts
+        .position
+        .value_at(ctx.accounts.pool.apy_bps, Clock::get()?.unix_timestamp)
+}
@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{constants::*, errors::*, state::*};
+
+/// Adapter `withdraw`: send `amount` of the position back to the owner
+///
+/// Account order follows the vault's strategy adapter interface.
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = asset_mint,
+        token::authority = owner,
+        token::token_program = token_program,
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub asset_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(has_one = asset_mint, has_one = reserve)]
+    pub pool: Account<'info, LendingPool>,
+
+    #[account(mut)]
+    pub reserve: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [POSITION_SEED, pool.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump,
+    )]
+    pub position: Account<'info, Position>,
+}
+
+pub fn handler(ctx: Context<Withdraw>, amount: u64) -> Result<u64> {
+    require!(amount > 0, LendingError::ZeroAmount);
+    require!(
+        ctx.accounts.reserve.amount >= amount,
+        LendingError::InsufficientReserves
+    );
+
+    let position = &mut ctx.accounts.position;
+    position.accrue(ctx.accounts.pool.apy_bps, Clock::get()?.unix_timestamp)?;
+    position.value = position
+        .value
+        .checked_sub(amount)
+        .ok_or(LendingError::InsufficientPosition)?;
+
+    let pool = &ctx.accounts.pool;
+    let pool_seeds: &[&[u8]] = &[POOL_SEED, pool.asset_mint.as_ref(), &[pool.bump]];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.reserve.to_account_info(),
+                mint: ctx.accounts.asset_mint.to_account_info(),
+                to: ctx.accounts.owner_token_account.to_account_info(),
+                authority: pool.to_account_info(),
+            },
+            &[pool_seeds],
+        ),
+        amount,
+        ctx.accounts.asset_mint.decimals,
+    )?;
+
+    Ok(amount)
+}
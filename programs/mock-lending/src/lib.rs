@@ -0,0 +1,59 @@
+// Mock Lending - reference strategy adapter for the tokenized vault
+// A simple-interest lending pool with configurable APY and loss injection,
+// for testing invest, harvest and loss flows on a local validator.
+// Not for production use.
+
+use anchor_lang::prelude::*;
+
+pub mod constants;
+pub mod errors;
+pub mod instructions;
+pub mod state;
+
+use instructions::*;
+
+declare_id!("ALD2qDFMqLEUTkuN6roc8Qqy2F193Fgby5RhkTNRbdU");
+
+#[program]
+pub mod mock_lending {
+    use super::*;
+
+    /// Create a pool and its reserve token account for an asset mint
+    pub fn initialize_pool(ctx: Context<InitializePool>, apy_bps: u32) -> Result<()> {
+        instructions::initialize_pool::handler(ctx, apy_bps)
+    }
+
+    /// Change the simple annual interest rate (pool authority only), accruing
+    /// every position at the old rate first
+    pub fn set_apy<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SetApy<'info>>,
+        apy_bps: u32,
+    ) -> Result<()> {
+        instructions::set_apy::handler(ctx, apy_bps)
+    }
+
+    /// Create an empty position for an owner that cannot pay rent itself
+    pub fn open_position(ctx: Context<OpenPosition>) -> Result<()> {
+        instructions::open_position::handler(ctx)
+    }
+
+    /// Strategy adapter `deposit`; returns the assets deposited
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<u64> {
+        instructions::deposit::handler(ctx, amount)
+    }
+
+    /// Strategy adapter `withdraw`; returns the assets withdrawn
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<u64> {
+        instructions::withdraw::handler(ctx, amount)
+    }
+
+    /// Strategy adapter `total_value`; returns the position value
+    pub fn total_value(ctx: Context<TotalValue>) -> Result<u64> {
+        instructions::total_value::handler(ctx)
+    }
+
+    /// Write a position down to simulate a loss (pool authority only)
+    pub fn inject_loss(ctx: Context<InjectLoss>, amount: u64) -> Result<()> {
+        instructions::inject_loss::handler(ctx, amount)
+    }
+}
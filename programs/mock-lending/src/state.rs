@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::*, errors::LendingError};
+
+/// Lending pool for one asset mint
+///
+/// Interest is paid out of the reserve token account, so the pool authority
+/// funds the reserve with enough tokens to cover the configured APY.
+#[account]
+pub struct LendingPool {
+    /// Can change the APY and inject losses
+    pub authority: Pubkey, // 32 bytes
+
+    /// Mint of the lent asset
+    pub asset_mint: Pubkey, // 32 bytes
+
+    /// Token account holding deposits and interest reserves
+    pub reserve: Pubkey, // 32 bytes
+
+    /// Simple annual interest paid on positions, in basis points
+    pub apy_bps: u32, // 4 bytes
+
+    /// Bump seed for pool PDA
+    pub bump: u8, // 1 byte
+
+    /// Bump seed for reserve PDA
+    pub reserve_bump: u8, // 1 byte
+
+    /// Positions opened in this pool, all of which `set_apy` must accrue
+    pub position_count: u32, // 4 bytes
+}
+
+impl LendingPool {
+    /// Space calculation:
+    /// 8 (discriminator) + 32 (authority) + 32 (asset_mint) + 32 (reserve)
+    /// + 4 (apy_bps) + 1 (bump) + 1 (reserve_bump) + 4 (position_count)
+    pub const SPACE: usize = 8 + 32 + 32 + 32 + 4 + 1 + 1 + 4;
+}
+
+/// One depositor's position in a pool (for the vault, its `strategy_authority`)
+#[account]
+pub struct Position {
+    /// Pool this position belongs to
+    pub pool: Pubkey, // 32 bytes
+
+    /// Depositor; signs deposits and withdrawals
+    pub owner: Pubkey, // 32 bytes
+
+    /// Value including interest as of `last_accrued_at`
+    pub value: u64, // 8 bytes
+
+    /// Unix timestamp interest was last accrued to
+    pub last_accrued_at: i64, // 8 bytes
+
+    /// Bump seed for PDA
+    pub bump: u8, // 1 byte
+}
+
+impl Position {
+    /// Space calculation:
+    /// 8 (discriminator) + 32 (pool) + 32 (owner) + 8 (value) + 8 (last_accrued_at) + 1 (bump)
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 8 + 1;
+
+    /// Value at `now`, with simple interest since the last accrual
+    pub fn value_at(&self, apy_bps: u32, now: i64) -> Result<u64> {
+        let elapsed = now.saturating_sub(self.last_accrued_at).max(0) as u128;
+        let interest = (self.value as u128)
+            .checked_mul(apy_bps as u128)
+            .and_then(|v| v.checked_mul(elapsed))
+            .ok_or(LendingError::MathOverflow)?
+            / (BPS_DENOMINATOR as u128 * SECONDS_PER_YEAR as u128);
+
+        u64::try_from(interest)
+            .ok()
+            .and_then(|interest| self.value.checked_add(interest))
+            .ok_or(error!(LendingError::MathOverflow))
+    }
+
+    /// Roll accrued interest into `value`
+    pub fn accrue(&mut self, apy_bps: u32, now: i64) -> Result<()> {
+        self.value = self.value_at(apy_bps, now)?;
+        self.last_accrued_at = now;
+        Ok(())
+    }
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use mock_lending::{constants::*, state::Position};
+
+fn position(value: u64, last_accrued_at: i64) -> Position {
+    Position {
+        pool: Pubkey::new_unique(),
+        owner: Pubkey::new_unique(),
+        value,
+        last_accrued_at,
+        bump: 0,
+    }
+}
+
+#[test]
+fn test_simple_interest_over_one_year() {
+    // 10% APY for a full year adds 10%
+    let position = position(1_000_000, 0);
+    let value = position.value_at(1_000, SECONDS_PER_YEAR as i64).unwrap();
+    assert_eq!(value, 1_100_000);
+}
+
+#[test]
+fn test_interest_rounds_down_and_ignores_clock_skew() {
+    // Sub-unit interest is not paid, and a clock behind the last accrual pays nothing
+    let position = position(1_000, 100);
+    assert_eq!(position.value_at(1_000, 101).unwrap(), 1_000);
+    assert_eq!(position.value_at(1_000, 50).unwrap(), 1_000);
+}
+
+#[test]
+fn test_accrue_rolls_interest_into_value() {
+    let mut position = position(1_000_000, 0);
+    position
+        .accrue(MAX_APY_BPS, SECONDS_PER_YEAR as i64)
+        .unwrap();
+    assert_eq!(position.value, 101_000_000, "10000% APY multiplies by 101");
+    assert_eq!(position.last_accrued_at, SECONDS_PER_YEAR as i64);
+
+    // Nothing more accrues at the same timestamp
+    assert_eq!(
+        position
+            .value_at(MAX_APY_BPS, SECONDS_PER_YEAR as i64)
+            .unwrap(),
+        101_000_000
+    );
+}
+
+#[test]
+fn test_interest_overflow_is_an_error() {
+    let position = position(u64::MAX, 0);
+    assert!(position
+        .value_at(MAX_APY_BPS, SECONDS_PER_YEAR as i64)
+        .is_err());
+}
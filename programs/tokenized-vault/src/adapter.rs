@@ -0,0 +1,137 @@
+//! Strategy adapter CPI interface
+//!
+//! An adapter is a program that puts vault assets to work (a lending market,
+//! stake pool, another vault, ...) and reports what the position is worth. It
+//! is whitelisted as an `ApprovedProtocol` of kind `Adapter` whose `target` is
+//! the adapter program ID, and is driven by `invest_strategy`,
+//! `divest_strategy` and `report_strategy`.
+//!
+//! Instruction data is an Anchor-style discriminator (first 8 bytes of
+//! `sha256("global:<name>")`) followed by Borsh arguments, so an adapter can be
+//! an ordinary Anchor program. Every instruction returns its result as a
+//! little-endian `u64` through `set_return_data`.
+//!
+//! Adapters never see `vault_authority`. Each adapter gets its own strategy
+//! authority PDA (`[STRATEGY_SEED, vault_state, adapter_program]`) whose only
+//! asset is its strategy token account (the PDA's ATA) and its position in the
+//! adapter. The vault funds that account with exactly `amount` before
+//! `deposit`, and sweeps what `withdraw` returned back to the vault after it.
+//!
+//! Accounts, in order:
+//! 0. `strategy_authority` - signer for `deposit` / `withdraw`, read-only for `total_value`
+//! 1. `strategy_token_account` - writable for `deposit` / `withdraw`, read-only for `total_value`
+//! 2. `asset_mint`
+//! 3. `token_program`
+//! 4. Adapter-specific accounts, passed through from `remaining_accounts`
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::{get_return_data, invoke_signed},
+};
+
+use crate::errors::VaultError;
+
+/// `deposit(amount: u64) -> u64`: pull `amount` from the strategy token account
+/// into the strategy, returning the assets deposited
+pub const DEPOSIT_DISCRIMINATOR: [u8; 8] = [242, 35, 198, 137, 82, 225, 242, 182];
+
+/// `withdraw(amount: u64) -> u64`: send `amount` from the strategy to the
+/// strategy token account, returning the assets withdrawn
+pub const WITHDRAW_DISCRIMINATOR: [u8; 8] = [183, 18, 70, 156, 148, 109, 161, 34];
+
+/// `total_value() -> u64`: current value of the vault's position, in asset units
+pub const TOTAL_VALUE_DISCRIMINATOR: [u8; 8] = [162, 27, 165, 173, 18, 43, 67, 234];
+
+/// Accounts forwarded to a strategy adapter
+pub struct AdapterAccounts<'a, 'info> {
+    pub adapter_program: AccountInfo<'info>,
+    pub strategy_authority: AccountInfo<'info>,
+    pub strategy_token_account: AccountInfo<'info>,
+    pub asset_mint: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub remaining_accounts: &'a [AccountInfo<'info>],
+}
+
+impl<'a, 'info> AdapterAccounts<'a, 'info> {
+    /// Move `amount` into the strategy, signed by `strategy_authority`
+    pub fn deposit(&self, amount: u64, signer_seeds: &[&[&[u8]]]) -> Result<u64> {
+        self.invoke(&DEPOSIT_DISCRIMINATOR, Some(amount), signer_seeds)
+    }
+
+    /// Move `amount` back to the strategy token account, signed by `strategy_authority`
+    pub fn withdraw(&self, amount: u64, signer_seeds: &[&[&[u8]]]) -> Result<u64> {
+        self.invoke(&WITHDRAW_DISCRIMINATOR, Some(amount), signer_seeds)
+    }
+
+    /// Read the position value
+    ///
+    /// Security: Unsigned and read-only, so a report cannot move vault funds
+    pub fn total_value(&self) -> Result<u64> {
+        self.invoke(&TOTAL_VALUE_DISCRIMINATOR, None, &[])
+    }
+
+    fn invoke(
+        &self,
+        discriminator: &[u8; 8],
+        amount: Option<u64>,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<u64> {
+        let signed = !signer_seeds.is_empty();
+
+        let mut data = discriminator.to_vec();
+        if let Some(amount) = amount {
+            data.extend_from_slice(&amount.to_le_bytes());
+        }
+
+        let mut accounts = vec![
+            AccountMeta {
+                pubkey: self.strategy_authority.key(),
+                is_signer: signed,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: self.strategy_token_account.key(),
+                is_signer: false,
+                is_writable: signed,
+            },
+            AccountMeta::new_readonly(self.asset_mint.key(), false),
+            AccountMeta::new_readonly(self.token_program.key(), false),
+        ];
+        accounts.extend(self.remaining_accounts.iter().map(|info| AccountMeta {
+            pubkey: info.key(),
+            is_signer: info.is_signer,
+            is_writable: info.is_writable,
+        }));
+
+        let mut infos = vec![
+            self.strategy_authority.clone(),
+            self.strategy_token_account.clone(),
+            self.asset_mint.clone(),
+            self.token_program.clone(),
+        ];
+        infos.extend_from_slice(self.remaining_accounts);
+        infos.push(self.adapter_program.clone());
+
+        let instruction = Instruction {
+            program_id: self.adapter_program.key(),
+            accounts,
+            data,
+        };
+        invoke_signed(&instruction, &infos, signer_seeds)?;
+
+        // Security: Only accept return data set by the adapter itself
+        let (program_id, return_data) =
+            get_return_data().ok_or(VaultError::InvalidAdapterReturn)?;
+        require_keys_eq!(
+            program_id,
+            self.adapter_program.key(),
+            VaultError::InvalidAdapterReturn
+        );
+        let value: [u8; 8] = return_data
+            .as_slice()
+            .try_into()
+            .map_err(|_| VaultError::InvalidAdapterReturn)?;
+        Ok(u64::from_le_bytes(value))
+    }
+}
@@ -9,9 +9,80 @@ pub const SHARE_MINT_SEED: &[u8] = b"shares";
 /// Seed for vault token account PDA
 pub const VAULT_AUTHORITY_SEED: &[u8] = b"vault_authority";
 
+/// Seed for per-protocol whitelist entry PDA
+pub const PROTOCOL_SEED: &[u8] = b"protocol";
+
+/// Seed for the program-wide config PDA
+pub const CONFIG_SEED: &[u8] = b"config";
+
+/// Seed for the per-mint allowlist entry PDA
+pub const ALLOWED_MINT_SEED: &[u8] = b"allowed_mint";
+
+/// Seed for the vault index entry PDA (followed by the little-endian index)
+pub const VAULT_INDEX_SEED: &[u8] = b"vault_index";
+
+/// Seed for the per-depositor position PDA
+pub const USER_POSITION_SEED: &[u8] = b"user_position";
+
+/// Seed for the temporary wSOL account used by deposit_sol / withdraw_sol
+pub const WSOL_TEMP_SEED: &[u8] = b"wsol_temp";
+
+/// Seed for the per-user asynchronous redeem request PDA
+pub const REDEEM_REQUEST_SEED: &[u8] = b"redeem_request";
+
+/// Seed for the vault's share escrow token account holding pending redeems
+/// and locked deposit shares
+pub const SHARE_ESCROW_SEED: &[u8] = b"share_escrow";
+
+/// Seed for the per-referrer volume PDA
+pub const REFERRAL_SEED: &[u8] = b"referral";
+
+/// Seed for the per-adapter strategy authority PDA that signs adapter CPIs
+pub const STRATEGY_SEED: &[u8] = b"strategy";
+
+/// Basis points denominator (100%)
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Maximum length of a protocol metadata URI
+pub const MAX_PROTOCOL_URI_LEN: usize = 200;
+
+/// Maximum length of the share token name
+pub const MAX_SHARE_NAME_LEN: usize = 32;
+
+/// Maximum length of the share token symbol
+pub const MAX_SHARE_SYMBOL_LEN: usize = 10;
+
+/// Maximum length of the share token metadata URI
+pub const MAX_SHARE_URI_LEN: usize = 200;
+
+/// Maximum Merkle proof length accepted by deposits (trees up to 2^20 depositors)
+pub const MAX_MERKLE_PROOF_LEN: usize = 20;
+
+/// Maximum deposit lockup duration (365 days)
+pub const MAX_LOCKUP_DURATION: i64 = 365 * 24 * 60 * 60;
+
 /// Space for VaultState account (8 discriminator + 32 authority + 32 asset_mint + 
 /// 32 share_mint + 8 total_assets + 8 total_shares + 1 bump + 1 share_bump + 
-/// 1 authority_bump + 128 padding)
-pub const VAULT_STATE_SIZE: usize = 8 + 32 + 32 + 32 + 8 + 8 + 1 + 1 + 1 + 128;
+/// 1 authority_bump + 8 vault_id + 8 deposit_cap + 8 per_user_deposit_cap +
+/// 32 depositor_merkle_root + 8 lockup_duration + 8 pending_redeem_shares +
+/// 8 claimable_assets + 2 min_idle_bps + 2 referral_fee_bps + 1 is_shutdown +
+/// 1 shutdown_reason + 1 version + 1 indexed + 40 padding)
+///
+/// Unchanged from the original layout: later fields reuse padding bytes, so
+/// existing vaults read back with vault_id = 0, no deposit caps, no lockup
+/// no open redeem requests, no idle buffer, no referral fee, not shut down,
+/// version 0 and not in the vault index.
+pub const VAULT_STATE_SIZE: usize =
+    8 + 32 + 32 + 32 + 8 + 8 + 1 + 1 + 1 + 8 + 8 + 8 + 32 + 8 + 8 + 8 + 2 + 2 + 1 + 1 + 1 + 1 + 40;
+
+/// Current VaultState layout version, written by `initialize` and `migrate_vault`
+pub const VAULT_STATE_VERSION: u8 = 1;
+
+/// Current ProtocolRegistry layout version
+pub const PROTOCOL_REGISTRY_VERSION: u8 = 1;
+
+/// Space of registries created before protocols moved to their own PDAs
+/// (8 discriminator + 32 vault + 4 vec len + 10 * 80 entries + 1 bump + 128 padding)
+pub const LEGACY_PROTOCOL_REGISTRY_SPACE: usize = 8 + 32 + 4 + (10 * 80) + 1 + 128;
 
 
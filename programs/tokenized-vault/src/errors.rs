@@ -38,6 +38,8 @@ pub enum VaultError {
     #[msg("Protocol not approved - target not in whitelist or disabled")]
     ProtocolNotApproved,
 
+    // The next three are no longer returned now that each protocol has its own
+    // PDA (no fixed capacity); they are kept so later error codes stay stable.
     #[msg("Protocol already exists in registry")]
     ProtocolAlreadyExists,
 
@@ -49,5 +51,122 @@ pub enum VaultError {
 
     #[msg("Protocol name too long - maximum 32 characters")]
     NameTooLong,
+
+    #[msg("Protocol still has invested funds - cannot remove")]
+    ProtocolHasInvestments,
+
+    #[msg("Investment exceeds the protocol's invest cap")]
+    ProtocolCapExceeded,
+
+    #[msg("Protocol metadata URI too long - maximum 200 characters")]
+    UriTooLong,
+
+    #[msg("Target token account owner does not match the approved protocol")]
+    TargetOwnerMismatch,
+
+    #[msg("Invalid target weight - weights must total at most 10000 bps")]
+    InvalidTargetWeight,
+
+    #[msg("Rebalance accounts must be (protocol, target token account) pairs for this vault")]
+    InvalidRebalanceAccounts,
+
+    #[msg("Vault cannot sign withdrawals from this protocol's target account")]
+    TargetNotWithdrawable,
+
+    #[msg("Share metadata too long - name max 32, symbol max 10, uri max 200 characters")]
+    ShareMetadataTooLong,
+
+    #[msg("Share metadata requires the share mint to use Token-2022")]
+    ShareMetadataRequiresToken2022,
+
+    #[msg("Share mint has no Token-2022 metadata")]
+    ShareMetadataNotFound,
+
+    #[msg("Vault asset is not the native SOL mint")]
+    NotNativeMint,
+
+    #[msg("Withdraw amount must be greater than zero")]
+    ZeroWithdrawAmount,
+
+    #[msg("Not enough idle assets in the vault to cover the withdrawal")]
+    InsufficientLiquidity,
+
+    #[msg("Asset mint is not allowed by the program config")]
+    MintNotAllowed,
+
+    #[msg("Fee too high - maximum 10000 bps")]
+    FeeTooHigh,
+
+    #[msg("Deposit would exceed the vault's deposit cap")]
+    DepositCapExceeded,
+
+    #[msg("Deposit would exceed the per-user deposit cap")]
+    UserDepositCapExceeded,
+
+    #[msg("Depositor is not in the vault's allowlist - invalid Merkle proof")]
+    DepositorNotAllowed,
+
+    #[msg("Lockup duration must be between 0 and 365 days")]
+    InvalidLockupDuration,
+
+    #[msg("Shares are still locked - wait until the position's unlock time")]
+    SharesLocked,
+
+    #[msg("Redeem request has already been fulfilled")]
+    RedeemAlreadyFulfilled,
+
+    #[msg("Redeem request has not been fulfilled yet")]
+    RedeemNotFulfilled,
+
+    #[msg("Fulfill accounts must be distinct pending redeem requests for this vault")]
+    InvalidRedeemRequest,
+
+    #[msg("Minimum idle buffer too high - maximum 10000 bps")]
+    InvalidMinIdleBps,
+
+    #[msg("Investment would leave idle assets below the vault's minimum buffer")]
+    IdleBufferBreached,
+
+    #[msg("Referrer must differ from the depositor and match the referral stats account")]
+    InvalidReferrer,
+
+    #[msg("Vault is shut down - only withdrawals and redemptions are allowed")]
+    VaultIsShutdown,
+
+    #[msg("Vault is not shut down")]
+    VaultNotShutdown,
+
+    #[msg("Vault still holds shares, assets, redeem requests or protocol entries")]
+    VaultNotEmpty,
+
+    #[msg("Vault is already on the current layout version")]
+    VaultAlreadyMigrated,
+
+    #[msg("Migration accounts do not match the vault or its legacy protocol entries")]
+    InvalidMigrationAccounts,
+
+    #[msg("Protocol is not a strategy adapter")]
+    InvalidAdapter,
+
+    #[msg("Strategy adapter returned no amount or moved a different amount than reported")]
+    InvalidAdapterReturn,
+
+    #[msg("Divest amount exceeds the amount invested in the protocol")]
+    DivestAmountTooLarge,
+
+    #[msg("Vault already has an entry in the vault index")]
+    VaultAlreadyIndexed,
+
+    #[msg("Position has no locked shares to release")]
+    NoLockedShares,
+
+    #[msg("Only token account targets not owned by the vault can be written off")]
+    WriteOffNotAllowed,
+
+    #[msg("Protocol has no invested amount to write off")]
+    NothingToWriteOff,
+
+    #[msg("Share escrow account is required while the vault has a lockup")]
+    ShareEscrowRequired,
 }
 
@@ -1,12 +1,58 @@
 use anchor_lang::prelude::*;
 
+use crate::state::ProtocolKind;
+
 /// Event emitted when a new vault is initialized
 #[event]
 pub struct VaultInitialized {
     pub vault: Pubkey,
     pub authority: Pubkey,
     pub asset_mint: Pubkey,
+    pub vault_id: u64,
+    pub share_mint: Pubkey,
+    /// Position of the vault in the program's vault index
+    pub index: u64,
+    pub creation_fee_lamports: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the program config is created or updated
+#[event]
+pub struct ConfigUpdated {
+    pub admin: Pubkey,
+    pub fee_recipient: Pubkey,
+    pub creation_fee_lamports: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a vault created before the program config is added to
+/// the vault index
+#[event]
+pub struct VaultRegistered {
+    pub vault: Pubkey,
+    pub asset_mint: Pubkey,
+    pub vault_id: u64,
+    /// Position of the vault in the program's vault index
+    pub index: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when an asset mint is allowed or disallowed
+#[event]
+pub struct AssetMintAllowed {
+    pub mint: Pubkey,
+    pub allowed: bool,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the share mint's metadata is set or updated
+#[event]
+pub struct ShareMetadataUpdated {
+    pub vault: Pubkey,
     pub share_mint: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
     pub timestamp: i64,
 }
 
@@ -15,13 +61,207 @@ pub struct VaultInitialized {
 pub struct Deposited {
     pub vault: Pubkey,
     pub user: Pubkey,
+    /// Amount the user sent
     pub asset_amount: u64,
+    /// Amount the vault received after any Token-2022 transfer fee
+    pub assets_received: u64,
     pub shares_minted: u64,
+    /// User's cumulative deposits after this one
+    pub user_total_deposited: u64,
+    /// Distribution partner credited with this deposit, if any
+    pub referrer: Option<Pubkey>,
+    /// When the user's locked shares can be released (minted to the share
+    /// escrow instead of the user while the vault has a lockup)
+    pub unlock_at: i64,
     pub total_assets: u64,
     pub total_shares: u64,
     pub timestamp: i64,
 }
 
+/// Event emitted when shares are redeemed for assets
+#[event]
+pub struct Withdrawn {
+    pub vault: Pubkey,
+    pub user: Pubkey,
+    pub shares_burned: u64,
+    pub assets_withdrawn: u64,
+    pub total_assets: u64,
+    pub total_shares: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the vault's deposit cap changes
+#[event]
+pub struct DepositCapSet {
+    pub vault: Pubkey,
+    /// New cap on total_assets (0 = uncapped)
+    pub deposit_cap: u64,
+    pub total_assets: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the vault's per-user deposit cap changes
+#[event]
+pub struct UserDepositCapSet {
+    pub vault: Pubkey,
+    /// New cap on each user's cumulative deposits (0 = uncapped)
+    pub per_user_deposit_cap: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the depositor allowlist root is rotated
+#[event]
+pub struct DepositorRootSet {
+    pub vault: Pubkey,
+    /// New Merkle root (all zeros = open to everyone)
+    pub depositor_merkle_root: [u8; 32],
+    pub timestamp: i64,
+}
+
+/// Event emitted when locked deposit shares are released to their owner
+#[event]
+pub struct SharesUnlocked {
+    pub vault: Pubkey,
+    pub user: Pubkey,
+    pub shares: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the deposit lockup duration changes
+#[event]
+pub struct LockupDurationSet {
+    pub vault: Pubkey,
+    /// Seconds each new deposit stays locked (0 = no lockup)
+    pub lockup_duration: i64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the minimum idle buffer changes
+#[event]
+pub struct MinIdleSet {
+    pub vault: Pubkey,
+    /// Share of total_assets kept idle, in bps
+    pub min_idle_bps: u16,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the referral rate metadata changes
+#[event]
+pub struct ReferralFeeSet {
+    pub vault: Pubkey,
+    /// Referral rate for off-chain attribution, in bps
+    pub referral_fee_bps: u16,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the authority permanently shuts the vault down
+#[event]
+pub struct VaultShutdown {
+    pub vault: Pubkey,
+    /// Operator-defined reason code
+    pub reason: u8,
+    pub total_assets: u64,
+    pub total_shares: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a shut-down vault recalls funds from a protocol
+#[event]
+pub struct FundsRecalled {
+    pub vault: Pubkey,
+    pub target: Pubkey,
+    pub amount: u64,
+    /// Amount still tracked as invested in the protocol afterwards
+    pub invested_amount: u64,
+    /// Yield recalled above the tracked investment, added to total_assets
+    pub gain: u64,
+    /// Shortfall the target could not return, written off total_assets
+    pub loss: u64,
+    pub caller: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the authority writes off a target the vault cannot withdraw from
+#[event]
+pub struct ProtocolWrittenOff {
+    pub vault: Pubkey,
+    pub target: Pubkey,
+    /// Invested amount removed from total_assets
+    pub loss: u64,
+    pub total_assets: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a vault is upgraded to the current layout
+#[event]
+pub struct VaultMigrated {
+    pub vault: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+    /// Whether a protocol registry existed and was upgraded too
+    pub registry_migrated: bool,
+    /// ApprovedProtocol accounts created from inline legacy registry entries
+    pub protocols_created: u32,
+    pub timestamp: i64,
+}
+
+/// Event emitted when an emptied vault is closed
+#[event]
+pub struct VaultClosed {
+    pub vault: Pubkey,
+    pub authority: Pubkey,
+    /// Lamports returned to the authority from all closed accounts
+    pub rent_reclaimed: u64,
+    /// Unowed asset balance swept from vault_token_account to the authority
+    pub assets_swept: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a user escrows shares for asynchronous redemption
+#[event]
+pub struct RedeemRequested {
+    pub vault: Pubkey,
+    pub user: Pubkey,
+    pub shares: u64,
+    pub pending_redeem_shares: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a pending redeem request is canceled
+#[event]
+pub struct RedeemCanceled {
+    pub vault: Pubkey,
+    pub user: Pubkey,
+    pub shares: u64,
+    pub pending_redeem_shares: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the allocator fulfills a batch of redeem requests
+#[event]
+pub struct RedeemsFulfilled {
+    pub vault: Pubkey,
+    pub request_count: u32,
+    /// Escrowed shares burned by this batch
+    pub shares_burned: u64,
+    /// Assets set aside for claims at the batch exchange rate
+    pub assets: u64,
+    pub total_assets: u64,
+    pub total_shares: u64,
+    pub claimable_assets: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a user collects the assets of a fulfilled request
+#[event]
+pub struct RedeemClaimed {
+    pub vault: Pubkey,
+    pub user: Pubkey,
+    pub shares: u64,
+    pub assets: u64,
+    pub timestamp: i64,
+}
+
 /// Event emitted when vault assets are invested
 #[event]
 pub struct Invested {
@@ -29,17 +269,49 @@ pub struct Invested {
     pub authority: Pubkey,
     pub target: Pubkey,
     pub protocol_name: String,
+    pub protocol_kind: ProtocolKind,
+    pub risk_tier: u8,
     pub amount: u64,
     pub total_assets: u64,
     pub timestamp: i64,
 }
 
+/// Event emitted when assets are withdrawn from a strategy adapter
+#[event]
+pub struct Divested {
+    pub vault: Pubkey,
+    pub target: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    /// Amount still tracked as invested in the protocol afterwards
+    pub invested_amount: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a strategy adapter's value is booked into the vault
+#[event]
+pub struct StrategyReported {
+    pub vault: Pubkey,
+    pub target: Pubkey,
+    pub previous_value: u64,
+    pub value: u64,
+    pub gain: u64,
+    pub loss: u64,
+    pub total_assets: u64,
+    pub timestamp: i64,
+}
+
 /// Event emitted when a protocol is added to the registry
 #[event]
 pub struct ProtocolAdded {
     pub vault: Pubkey,
     pub target: Pubkey,
     pub name: String,
+    pub expected_owner: Pubkey,
+    pub kind: ProtocolKind,
+    pub owner_program: Pubkey,
+    pub risk_tier: u8,
+    pub uri: String,
     pub timestamp: i64,
 }
 
@@ -52,3 +324,47 @@ pub struct ProtocolToggled {
     pub timestamp: i64,
 }
 
+/// Event emitted when a protocol is removed from the registry
+#[event]
+pub struct ProtocolRemoved {
+    pub vault: Pubkey,
+    pub target: Pubkey,
+    pub name: String,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a protocol's target weight changes
+#[event]
+pub struct TargetWeightSet {
+    pub vault: Pubkey,
+    pub target: Pubkey,
+    pub target_weight_bps: u16,
+    pub total_target_weight_bps: u16,
+    pub timestamp: i64,
+}
+
+/// Event emitted for each transfer made by `rebalance`
+#[event]
+pub struct Rebalanced {
+    pub vault: Pubkey,
+    pub target: Pubkey,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub amount: u64,
+    pub invested_amount: u64,
+    pub target_weight_bps: u16,
+    pub total_assets: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when `rebalance` cannot withdraw an over-allocated protocol
+/// because its target is not owned by the vault
+#[event]
+pub struct RebalanceSkipped {
+    pub vault: Pubkey,
+    pub target: Pubkey,
+    pub excess: u64,
+    pub invested_amount: u64,
+    pub target_weight_bps: u16,
+    pub timestamp: i64,
+}
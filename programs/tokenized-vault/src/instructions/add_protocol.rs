@@ -5,6 +5,7 @@ use crate::{constants::*, errors::*, events::*, state::*};
 /// Add a new protocol to the approved whitelist
 ///
 #[derive(Accounts)]
+#[instruction(target: Pubkey)]
 pub struct AddProtocol<'info> {
     /// Vault authority - only they can manage protocols
     /// Security: Must be signer and match vault_state.authority
@@ -14,7 +15,7 @@ pub struct AddProtocol<'info> {
     /// Vault state PDA
     /// Security: has_one constraint validates authority from state
     #[account(
-        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref()],
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
         bump = vault_state.bump,
         has_one = authority @ VaultError::Unauthorized,
     )]
@@ -31,55 +32,78 @@ pub struct AddProtocol<'info> {
     )]
     pub protocol_registry: Account<'info, ProtocolRegistry>,
 
+    /// Approved protocol entry PDA
+    /// Security: `init` fails if the target is already whitelisted for this vault
+    #[account(
+        init,
+        payer = authority,
+        space = ApprovedProtocol::SPACE,
+        seeds = [PROTOCOL_SEED, vault_state.key().as_ref(), target.as_ref()],
+        bump
+    )]
+    pub protocol: Account<'info, ApprovedProtocol>,
+
     pub system_program: Program<'info, System>,
 }
 
 pub fn handler(
     ctx: Context<AddProtocol>,
     target: Pubkey,
+    expected_owner: Pubkey,
     name: String,
+    invest_cap: Option<u64>,
+    metadata: ProtocolMetadata,
 ) -> Result<()> {
     // CHECKS: Validate inputs
     require!(name.len() <= 32, VaultError::NameTooLong);
+    require!(
+        metadata.uri.len() <= MAX_PROTOCOL_URI_LEN,
+        VaultError::UriTooLong
+    );
 
+    let vault_key = ctx.accounts.vault_state.key();
     let registry = &mut ctx.accounts.protocol_registry;
 
     // Initialize registry if first time
     if registry.vault == Pubkey::default() {
-        registry.vault = ctx.accounts.vault_state.key();
+        registry.vault = vault_key;
         registry.bump = ctx.bumps.protocol_registry;
-        registry.approved_protocols = Vec::new();
+        registry.protocol_count = 0;
+        registry.total_target_weight_bps = 0;
+        registry.version = PROTOCOL_REGISTRY_VERSION;
     }
 
-    // Check if protocol already exists
-    require!(
-        !registry.approved_protocols.iter().any(|p| p.target == target),
-        VaultError::ProtocolAlreadyExists
-    );
+    // EFFECTS: Create protocol entry and count it in the registry
+    registry.protocol_count = registry
+        .protocol_count
+        .checked_add(1)
+        .ok_or(VaultError::MathOverflow)?;
 
-    // Check registry capacity (max 10 protocols)
-    require!(
-        registry.approved_protocols.len() < 10,
-        VaultError::RegistryFull
-    );
-
-    // EFFECTS: Add protocol to registry
-    registry.approved_protocols.push(ApprovedProtocol {
-        target,
-        enabled: true,
-        invested_amount: 0,
-        name: name.clone(),
-    });
+    let protocol = &mut ctx.accounts.protocol;
+    protocol.vault = vault_key;
+    protocol.target = target;
+    protocol.expected_owner = expected_owner;
+    protocol.enabled = true;
+    protocol.invested_amount = 0;
+    protocol.invest_cap = invest_cap;
+    protocol.target_weight_bps = 0;
+    protocol.name = name.clone();
+    protocol.metadata = metadata.clone();
+    protocol.bump = ctx.bumps.protocol;
 
     // INTERACTIONS: Emit event
     emit!(ProtocolAdded {
-        vault: registry.vault,
+        vault: vault_key,
         target,
         name,
+        expected_owner,
+        kind: metadata.kind,
+        owner_program: metadata.owner_program,
+        risk_tier: metadata.risk_tier,
+        uri: metadata.uri,
         timestamp: Clock::get()?.unix_timestamp,
     });
 
     Ok(())
 }
 
-
@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{constants::*, errors::*, events::*, state::*};
+
+/// Cancel a pending redeem request and return the escrowed shares
+#[derive(Accounts)]
+pub struct CancelRedeem<'info> {
+    /// User who opened the request
+    /// Security: Must be signer, receives the shares and the request rent
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// Vault state PDA
+    /// Security: Validated by seeds, tracks pending redeem shares
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.bump,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// Share mint
+    /// Security: Must match vault_state.share_mint and share_token_program
+    #[account(
+        address = vault_state.share_mint,
+        mint::token_program = share_token_program,
+    )]
+    pub share_mint: InterfaceAccount<'info, Mint>,
+
+    /// Vault authority PDA
+    /// Security: CHECK constraint, validated by seeds
+    /// CHECK: PDA used as escrow authority, validated by seeds
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.authority_bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// User's share token account (destination)
+    /// Security: Must be owned by user and correct mint
+    #[account(
+        mut,
+        constraint = user_share_account.mint == vault_state.share_mint @ VaultError::InvalidMint,
+        constraint = user_share_account.owner == user.key() @ VaultError::InvalidOwner,
+    )]
+    pub user_share_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Vault-owned share escrow
+    /// Security: Validated by seeds
+    #[account(
+        mut,
+        seeds = [SHARE_ESCROW_SEED, vault_state.key().as_ref()],
+        bump,
+    )]
+    pub share_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    /// Redeem request PDA, closed back to the user
+    /// Security: Seeds bind it to this vault and user; must still be pending
+    #[account(
+        mut,
+        close = user,
+        seeds = [REDEEM_REQUEST_SEED, vault_state.key().as_ref(), user.key().as_ref()],
+        bump = redeem_request.bump,
+        constraint = !redeem_request.is_fulfilled() @ VaultError::RedeemAlreadyFulfilled,
+    )]
+    pub redeem_request: Account<'info, RedeemRequest>,
+
+    /// Token program that owns the share mint
+    pub share_token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler(ctx: Context<CancelRedeem>) -> Result<()> {
+    let shares = ctx.accounts.redeem_request.shares;
+
+    // EFFECTS: Release the pending shares before moving tokens
+    let vault_state = &mut ctx.accounts.vault_state;
+    vault_state.pending_redeem_shares = vault_state
+        .pending_redeem_shares
+        .checked_sub(shares)
+        .ok_or(VaultError::MathOverflow)?;
+
+    // INTERACTIONS: Return shares from escrow, signed by vault_authority
+    let asset_mint_key = vault_state.asset_mint;
+    let authority_bump = vault_state.authority_bump;
+    let id_seed = vault_id_seed(vault_state.vault_id);
+    let authority_seeds: &[&[u8]] = &[
+        VAULT_AUTHORITY_SEED,
+        asset_mint_key.as_ref(),
+        &id_seed,
+        &[authority_bump],
+    ];
+    let signer_seeds = &[authority_seeds];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.share_token_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.share_escrow.to_account_info(),
+            mint: ctx.accounts.share_mint.to_account_info(),
+            to: ctx.accounts.user_share_account.to_account_info(),
+            authority: ctx.accounts.vault_authority.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token_interface::transfer_checked(transfer_ctx, shares, ctx.accounts.share_mint.decimals)?;
+
+    // Emit event
+    emit!(RedeemCanceled {
+        vault: vault_state.key(),
+        user: ctx.accounts.user.key(),
+        shares,
+        pending_redeem_shares: vault_state.pending_redeem_shares,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{constants::*, errors::*, events::*, state::*};
+
+/// Collect the assets of a fulfilled redeem request
+#[derive(Accounts)]
+pub struct ClaimRedeem<'info> {
+    /// User who opened the request
+    /// Security: Must be signer, receives the assets and the request rent
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// Vault state PDA
+    /// Security: Validated by seeds, tracks claimable assets
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.bump,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// Asset mint
+    /// Security: Must match vault_state.asset_mint and asset_token_program
+    #[account(
+        address = vault_state.asset_mint,
+        mint::token_program = asset_token_program,
+    )]
+    pub asset_mint: InterfaceAccount<'info, Mint>,
+
+    /// Vault authority PDA
+    /// Security: CHECK constraint, validated by seeds
+    /// CHECK: PDA used as authority, validated by seeds
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.authority_bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// User's asset token account (destination)
+    /// Security: Must be owned by user and correct mint
+    #[account(
+        mut,
+        constraint = user_asset_account.mint == vault_state.asset_mint @ VaultError::InvalidMint,
+        constraint = user_asset_account.owner == user.key() @ VaultError::InvalidOwner,
+    )]
+    pub user_asset_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Vault's token account
+    /// Security: Must be the vault_authority ATA, not another vault-owned account
+    #[account(
+        mut,
+        associated_token::mint = asset_mint,
+        associated_token::authority = vault_authority,
+        associated_token::token_program = asset_token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Redeem request PDA, closed back to the user
+    /// Security: Seeds bind it to this vault and user; must be fulfilled
+    #[account(
+        mut,
+        close = user,
+        seeds = [REDEEM_REQUEST_SEED, vault_state.key().as_ref(), user.key().as_ref()],
+        bump = redeem_request.bump,
+        constraint = redeem_request.is_fulfilled() @ VaultError::RedeemNotFulfilled,
+    )]
+    pub redeem_request: Account<'info, RedeemRequest>,
+
+    /// Token program that owns the asset mint
+    pub asset_token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler(ctx: Context<ClaimRedeem>) -> Result<()> {
+    let shares = ctx.accounts.redeem_request.shares;
+    let assets = ctx.accounts.redeem_request.assets;
+
+    // EFFECTS: Release the reservation before moving tokens
+    let vault_state = &mut ctx.accounts.vault_state;
+    vault_state.claimable_assets = vault_state
+        .claimable_assets
+        .checked_sub(assets)
+        .ok_or(VaultError::MathOverflow)?;
+
+    // INTERACTIONS: Pay out the assets locked in at fulfillment
+    let asset_mint_key = vault_state.asset_mint;
+    let authority_bump = vault_state.authority_bump;
+    let id_seed = vault_id_seed(vault_state.vault_id);
+    let authority_seeds: &[&[u8]] = &[
+        VAULT_AUTHORITY_SEED,
+        asset_mint_key.as_ref(),
+        &id_seed,
+        &[authority_bump],
+    ];
+    let signer_seeds = &[authority_seeds];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.asset_token_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.vault_token_account.to_account_info(),
+            mint: ctx.accounts.asset_mint.to_account_info(),
+            to: ctx.accounts.user_asset_account.to_account_info(),
+            authority: ctx.accounts.vault_authority.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token_interface::transfer_checked(transfer_ctx, assets, ctx.accounts.asset_mint.decimals)?;
+
+    // Emit event
+    emit!(RedeemClaimed {
+        vault: vault_state.key(),
+        user: ctx.accounts.user.key(),
+        shares,
+        assets,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
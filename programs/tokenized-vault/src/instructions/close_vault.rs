@@ -0,0 +1,220 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_interface::{
+    self, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::{constants::*, errors::*, events::*, state::*};
+
+/// Close an emptied vault and return the rent of its accounts to the authority
+#[derive(Accounts)]
+pub struct CloseVault<'info> {
+    /// Vault authority - only they can close the vault
+    /// Security: Receives the rent of every closed account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Vault state PDA, closed by Anchor at the end of the instruction
+    /// Security: has_one validates authority; nothing may be owed to anyone
+    #[account(
+        mut,
+        close = authority,
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.bump,
+        has_one = authority @ VaultError::Unauthorized,
+        constraint = vault_state.is_empty() @ VaultError::VaultNotEmpty,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// Share mint
+    /// Security: No shares may be outstanding
+    #[account(
+        address = vault_state.share_mint,
+        mint::token_program = share_token_program,
+        constraint = share_mint.supply == 0 @ VaultError::VaultNotEmpty,
+    )]
+    pub share_mint: InterfaceAccount<'info, Mint>,
+
+    /// Asset mint
+    /// Security: Must match vault_state.asset_mint and asset_token_program
+    #[account(
+        address = vault_state.asset_mint,
+        mint::token_program = asset_token_program,
+    )]
+    pub asset_mint: InterfaceAccount<'info, Mint>,
+
+    /// Vault authority PDA
+    /// Security: CHECK constraint, validated by seeds
+    /// CHECK: PDA used as close authority, validated by seeds
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.authority_bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Vault's token account
+    /// Security: Must be the vault_authority ATA. With no shares outstanding
+    /// any balance is unowed (e.g. a donation) and is swept to the authority
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &vault_authority.key(),
+            &vault_state.asset_mint,
+            &asset_token_program.key(),
+        ) @ VaultError::InvalidOwner,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Authority's asset token account, receives any leftover balance
+    /// Security: Must be owned by authority and correct mint
+    #[account(
+        mut,
+        constraint = authority_asset_account.mint == vault_state.asset_mint @ VaultError::InvalidMint,
+        constraint = authority_asset_account.owner == authority.key() @ VaultError::InvalidOwner,
+    )]
+    pub authority_asset_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Protocol registry PDA, closed if it was ever created
+    /// Security: Validated by seeds; deserialized in the handler when present
+    /// CHECK: May not exist for vaults that never added a protocol
+    #[account(
+        mut,
+        seeds = [b"protocol_registry", vault_state.key().as_ref()],
+        bump,
+    )]
+    pub protocol_registry: UncheckedAccount<'info>,
+
+    /// Share escrow PDA, closed if any redeem was ever requested
+    /// Security: Validated by seeds; deserialized in the handler when present
+    /// CHECK: May not exist for vaults that never had a redeem request
+    #[account(
+        mut,
+        seeds = [SHARE_ESCROW_SEED, vault_state.key().as_ref()],
+        bump,
+    )]
+    pub share_escrow: UncheckedAccount<'info>,
+
+    /// Token program that owns the asset mint
+    pub asset_token_program: Interface<'info, TokenInterface>,
+
+    /// Token program that owns the share mint
+    pub share_token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler(ctx: Context<CloseVault>) -> Result<()> {
+    let authority_info = ctx.accounts.authority.to_account_info();
+    let lamports_before = authority_info.lamports();
+
+    // CHECKS: Every protocol entry must have been removed (remove_protocol
+    // requires invested_amount == 0), and the escrow must be empty
+    let registry_info = ctx.accounts.protocol_registry.to_account_info();
+    let has_registry = !registry_info.data_is_empty();
+    if has_registry {
+        // Security: Check program ownership, then the discriminator and layout
+        require_keys_eq!(*registry_info.owner, crate::ID, VaultError::InvalidOwner);
+        let registry = ProtocolRegistry::try_deserialize(&mut &registry_info.data.borrow()[..])?;
+        require!(registry.protocol_count == 0, VaultError::VaultNotEmpty);
+    }
+
+    let escrow_info = ctx.accounts.share_escrow.to_account_info();
+    let has_escrow = !escrow_info.data_is_empty();
+    if has_escrow {
+        require_keys_eq!(
+            *escrow_info.owner,
+            ctx.accounts.share_token_program.key(),
+            VaultError::InvalidOwner
+        );
+        let escrow = TokenAccount::try_deserialize(&mut &escrow_info.data.borrow()[..])?;
+        require!(escrow.amount == 0, VaultError::VaultNotEmpty);
+    }
+
+    // EFFECTS: With no shares left, any remaining total_assets is rounding
+    // dust owed to no one; its tokens go out with the sweep below
+    ctx.accounts.vault_state.total_assets = 0;
+
+    // INTERACTIONS: Sweep, then close the token accounts, signed by vault_authority
+    let vault_state = &ctx.accounts.vault_state;
+    let asset_mint_key = vault_state.asset_mint;
+    let authority_bump = vault_state.authority_bump;
+    let id_seed = vault_id_seed(vault_state.vault_id);
+    let authority_seeds: &[&[u8]] = &[
+        VAULT_AUTHORITY_SEED,
+        asset_mint_key.as_ref(),
+        &id_seed,
+        &[authority_bump],
+    ];
+    let signer_seeds = &[authority_seeds];
+
+    // Nothing is owed to holders, so a leftover balance cannot block the close
+    let assets_swept = ctx.accounts.vault_token_account.amount;
+    if assets_swept > 0 {
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.asset_token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault_token_account.to_account_info(),
+                mint: ctx.accounts.asset_mint.to_account_info(),
+                to: ctx.accounts.authority_asset_account.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token_interface::transfer_checked(
+            transfer_ctx,
+            assets_swept,
+            ctx.accounts.asset_mint.decimals,
+        )?;
+    }
+
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.asset_token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.vault_token_account.to_account_info(),
+            destination: authority_info.clone(),
+            authority: ctx.accounts.vault_authority.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    if has_escrow {
+        token_interface::close_account(CpiContext::new_with_signer(
+            ctx.accounts.share_token_program.to_account_info(),
+            CloseAccount {
+                account: escrow_info,
+                destination: authority_info.clone(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+    }
+
+    // Close the registry the way Anchor's `close` constraint does
+    if has_registry {
+        let registry_lamports = registry_info.lamports();
+        **authority_info.try_borrow_mut_lamports()? = authority_info
+            .lamports()
+            .checked_add(registry_lamports)
+            .ok_or(VaultError::MathOverflow)?;
+        **registry_info.try_borrow_mut_lamports()? = 0;
+        registry_info.assign(&anchor_lang::system_program::ID);
+        registry_info.resize(0)?;
+    }
+
+    // vault_state rent is returned by Anchor's `close` after this handler
+    let rent_reclaimed = authority_info
+        .lamports()
+        .checked_sub(lamports_before)
+        .ok_or(VaultError::MathOverflow)?
+        .checked_add(vault_state.to_account_info().lamports())
+        .ok_or(VaultError::MathOverflow)?;
+
+    // Emit event
+    emit!(VaultClosed {
+        vault: vault_state.key(),
+        authority: ctx.accounts.authority.key(),
+        rent_reclaimed,
+        assets_swept,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -1,10 +1,13 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{
+    self, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
+};
 
 use crate::{constants::*, errors::*, events::*, state::*};
 
 /// Deposit assets into the vault and receive shares
 #[derive(Accounts)]
+#[instruction(amount: u64, proof: Vec<[u8; 32]>, referrer: Option<Pubkey>)]
 pub struct Deposit<'info> {
     /// User depositing assets
     /// Security: Must be signer
@@ -15,31 +18,33 @@ pub struct Deposit<'info> {
     /// Security: Validated by seeds, contains authority and totals
     #[account(
         mut,
-        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref()],
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
         bump = vault_state.bump,
     )]
     pub vault_state: Account<'info, VaultState>,
 
     /// Asset mint
-    /// Security: Must match vault_state.asset_mint
+    /// Security: Must match vault_state.asset_mint and asset_token_program
     #[account(
         address = vault_state.asset_mint,
+        mint::token_program = asset_token_program,
     )]
-    pub asset_mint: Account<'info, Mint>,
+    pub asset_mint: InterfaceAccount<'info, Mint>,
 
     /// Share mint
-    /// Security: Must match vault_state.share_mint
+    /// Security: Must match vault_state.share_mint and share_token_program
     #[account(
         mut,
         address = vault_state.share_mint,
+        mint::token_program = share_token_program,
     )]
-    pub share_mint: Account<'info, Mint>,
+    pub share_mint: InterfaceAccount<'info, Mint>,
 
     /// Vault authority PDA
     /// Security: CHECK constraint, validated by seeds
     /// CHECK: PDA used as authority, validated by seeds
     #[account(
-        seeds = [VAULT_AUTHORITY_SEED, vault_state.asset_mint.as_ref()],
+        seeds = [VAULT_AUTHORITY_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
         bump = vault_state.authority_bump,
     )]
     pub vault_authority: UncheckedAccount<'info>,
@@ -51,7 +56,7 @@ pub struct Deposit<'info> {
         constraint = user_asset_account.mint == vault_state.asset_mint @ VaultError::InvalidMint,
         constraint = user_asset_account.owner == user.key() @ VaultError::InvalidOwner,
     )]
-    pub user_asset_account: Account<'info, TokenAccount>,
+    pub user_asset_account: InterfaceAccount<'info, TokenAccount>,
 
     /// User's share token account (destination)
     /// Security: Must be owned by user and correct mint
@@ -60,86 +65,248 @@ pub struct Deposit<'info> {
         constraint = user_share_account.mint == vault_state.share_mint @ VaultError::InvalidMint,
         constraint = user_share_account.owner == user.key() @ VaultError::InvalidOwner,
     )]
-    pub user_share_account: Account<'info, TokenAccount>,
+    pub user_share_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Vault-owned share escrow, receives the shares while the vault has a
+    /// lockup; required only then, and created on first use
+    /// Security: PDA token account whose authority is vault_authority
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [SHARE_ESCROW_SEED, vault_state.key().as_ref()],
+        bump,
+        token::mint = share_mint,
+        token::authority = vault_authority,
+        token::token_program = share_token_program,
+    )]
+    pub share_escrow: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// User's position PDA, created on first deposit
+    /// Security: Seeds bind it to this vault and user
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserPosition::SPACE,
+        seeds = [USER_POSITION_SEED, vault_state.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    /// Referrer's volume PDA, required when `referrer` is set
+    /// Security: Seeds bind it to this vault and the referrer argument
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = ReferralStats::SPACE,
+        seeds = [REFERRAL_SEED, vault_state.key().as_ref(), referrer.unwrap_or_default().as_ref()],
+        bump
+    )]
+    pub referral_stats: Option<Account<'info, ReferralStats>>,
 
     /// Vault's token account
-    /// Security: Must be correct mint and owned by vault_authority
+    /// Security: Must be the vault_authority ATA, not another vault-owned account
     #[account(
         mut,
-        constraint = vault_token_account.mint == vault_state.asset_mint @ VaultError::InvalidMint,
-        constraint = vault_token_account.owner == vault_authority.key() @ VaultError::InvalidOwner,
+        associated_token::mint = asset_mint,
+        associated_token::authority = vault_authority,
+        associated_token::token_program = asset_token_program,
     )]
-    pub vault_token_account: Account<'info, TokenAccount>,
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
 
-    pub token_program: Program<'info, Token>,
+    /// Token program that owns the asset mint
+    pub asset_token_program: Interface<'info, TokenInterface>,
+
+    /// Token program that owns the share mint
+    pub share_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<Deposit>, amount: u64) -> Result<()> {
-    // CHECKS: Validate amount
-    require!(amount > 0, VaultError::ZeroDepositAmount);
+/// Accounts `deposit` and `deposit_sol` share; only how the assets reach
+/// `vault_token_account` differs between them
+pub struct DepositAccounts<'a, 'info> {
+    pub user: &'a Signer<'info>,
+    pub vault_state: &'a mut Account<'info, VaultState>,
+    pub share_mint: &'a InterfaceAccount<'info, Mint>,
+    pub vault_authority: &'a UncheckedAccount<'info>,
+    pub user_share_account: &'a InterfaceAccount<'info, TokenAccount>,
+    pub share_escrow: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    pub user_position: &'a mut Account<'info, UserPosition>,
+    pub user_position_bump: u8,
+    pub referral_stats: Option<&'a mut Account<'info, ReferralStats>>,
+    pub referral_stats_bump: Option<u8>,
+    pub vault_token_account: &'a mut InterfaceAccount<'info, TokenAccount>,
+    pub share_token_program: &'a Interface<'info, TokenInterface>,
+}
+
+impl DepositAccounts<'_, '_> {
+    /// Validate the referrer, share escrow, allowlist and deposit caps for `amount`
+    pub fn check_deposit(
+        &self,
+        amount: u64,
+        proof: &[[u8; 32]],
+        referrer: Option<Pubkey>,
+    ) -> Result<()> {
+        require!(amount > 0, VaultError::ZeroDepositAmount);
+        self.vault_state.check_active()?;
+        require!(
+            referrer.is_some() == self.referral_stats.is_some()
+                && referrer != Some(self.user.key()),
+            VaultError::InvalidReferrer
+        );
+        require!(
+            self.vault_state.lockup_duration == 0 || self.share_escrow.is_some(),
+            VaultError::ShareEscrowRequired
+        );
+        self.vault_state.check_depositor(&self.user.key(), proof)?;
+        self.vault_state.check_deposit_cap(amount)?;
+        self.user_position
+            .check_deposit_cap(self.vault_state.per_user_deposit_cap, amount)
+    }
+
+    /// Mint shares for what reached `vault_token_account` since
+    /// `balance_before`, and record the deposit
+    ///
+    /// Security: Shares are priced only on assets that arrived, so Token-2022
+    /// transfer fees are not credited
+    pub fn credit_deposit(
+        &mut self,
+        amount: u64,
+        balance_before: u64,
+        referrer: Option<Pubkey>,
+    ) -> Result<()> {
+        self.vault_token_account.reload()?;
+        let assets_received = self
+            .vault_token_account
+            .amount
+            .checked_sub(balance_before)
+            .ok_or(VaultError::MathOverflow)?;
+        require!(assets_received > 0, VaultError::ZeroDepositAmount);
+
+        let vault_state = &mut *self.vault_state;
 
-    let vault_state = &mut ctx.accounts.vault_state;
+        // Calculate shares to mint using ERC-4626 formula, only for assets that arrived
+        let shares_to_mint = vault_state.calculate_shares(assets_received)?;
 
-    // Calculate shares to mint using ERC-4626 formula
-    let shares_to_mint = vault_state.calculate_shares(amount)?;
+        // EFFECTS: Update vault state BEFORE minting shares
+        vault_state.total_assets = vault_state
+            .total_assets
+            .checked_add(assets_received)
+            .ok_or(VaultError::MathOverflow)?;
 
-    // EFFECTS: Update vault state BEFORE external calls
-    vault_state.total_assets = vault_state
-        .total_assets
-        .checked_add(amount)
-        .ok_or(VaultError::MathOverflow)?;
+        vault_state.total_shares = vault_state
+            .total_shares
+            .checked_add(shares_to_mint)
+            .ok_or(VaultError::MathOverflow)?;
 
-    vault_state.total_shares = vault_state
-        .total_shares
-        .checked_add(shares_to_mint)
-        .ok_or(VaultError::MathOverflow)?;
+        let user_position = &mut *self.user_position;
+        user_position.record_deposit(
+            vault_state.key(),
+            self.user.key(),
+            self.user_position_bump,
+            assets_received,
+            shares_to_mint,
+            Clock::get()?.unix_timestamp,
+            vault_state.lockup_duration,
+        )?;
 
-    // INTERACTIONS: External calls after state updates
+        // Present exactly when a referrer was passed (checked in check_deposit)
+        if let Some(referral_stats) = self.referral_stats.as_deref_mut() {
+            referral_stats.record_deposit(
+                vault_state.key(),
+                referrer.unwrap_or_default(),
+                self.referral_stats_bump.unwrap_or_default(),
+                assets_received,
+                shares_to_mint,
+            )?;
+        }
+
+        // INTERACTIONS: Mint shares to user, or to the share escrow until the lockup ends
+        let asset_mint_key = vault_state.asset_mint;
+        let authority_bump = vault_state.authority_bump;
+        let id_seed = vault_id_seed(vault_state.vault_id);
+        let authority_seeds: &[&[u8]] = &[
+            VAULT_AUTHORITY_SEED,
+            asset_mint_key.as_ref(),
+            &id_seed,
+            &[authority_bump],
+        ];
+        let signer_seeds = &[authority_seeds];
+        // Present whenever the vault has a lockup (checked in check_deposit)
+        let shares_destination = match self.share_escrow {
+            Some(share_escrow) if vault_state.lockup_duration > 0 => share_escrow.to_account_info(),
+            _ => self.user_share_account.to_account_info(),
+        };
+
+        let mint_ctx = CpiContext::new_with_signer(
+            self.share_token_program.to_account_info(),
+            MintTo {
+                mint: self.share_mint.to_account_info(),
+                to: shares_destination,
+                authority: self.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token_interface::mint_to(mint_ctx, shares_to_mint)?;
+
+        // Emit event
+        emit!(Deposited {
+            vault: vault_state.key(),
+            user: self.user.key(),
+            asset_amount: amount,
+            assets_received,
+            shares_minted: shares_to_mint,
+            user_total_deposited: user_position.total_deposited,
+            unlock_at: user_position.unlock_at,
+            referrer,
+            total_assets: vault_state.total_assets,
+            total_shares: vault_state.total_shares,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
+
+pub fn handler(
+    ctx: Context<Deposit>,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+    referrer: Option<Pubkey>,
+) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    let mut deposit = DepositAccounts {
+        user: &accounts.user,
+        vault_state: &mut accounts.vault_state,
+        share_mint: &accounts.share_mint,
+        vault_authority: &accounts.vault_authority,
+        user_share_account: &accounts.user_share_account,
+        share_escrow: accounts.share_escrow.as_ref(),
+        user_position: &mut accounts.user_position,
+        user_position_bump: ctx.bumps.user_position,
+        referral_stats: accounts.referral_stats.as_mut(),
+        referral_stats_bump: ctx.bumps.referral_stats,
+        vault_token_account: &mut accounts.vault_token_account,
+        share_token_program: &accounts.share_token_program,
+    };
+
+    // CHECKS: Validate amount, referrer, share escrow, allowlist and deposit caps
+    deposit.check_deposit(amount, &proof, referrer)?;
+
+    // INTERACTIONS: Pull assets first so Token-2022 transfer fees can be measured.
+    // The transfer only moves the user's own tokens into the vault; no vault
+    // state has been read for pricing yet.
+    let balance_before = deposit.vault_token_account.amount;
 
-    // Transfer assets from user to vault
     let transfer_ctx = CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
-        Transfer {
-            from: ctx.accounts.user_asset_account.to_account_info(),
-            to: ctx.accounts.vault_token_account.to_account_info(),
-            authority: ctx.accounts.user.to_account_info(),
-        },
-    );
-    token::transfer(transfer_ctx, amount)?;
-
-    // Mint shares to user
-    let asset_mint_key = vault_state.asset_mint;
-    let authority_bump = vault_state.authority_bump;
-    let authority_seeds: &[&[u8]] = &[
-        VAULT_AUTHORITY_SEED,
-        asset_mint_key.as_ref(),
-        &[authority_bump],
-    ];
-    let signer_seeds = &[&authority_seeds[..]];
-
-    let mint_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        MintTo {
-            mint: ctx.accounts.share_mint.to_account_info(),
-            to: ctx.accounts.user_share_account.to_account_info(),
-            authority: ctx.accounts.vault_authority.to_account_info(),
+        accounts.asset_token_program.to_account_info(),
+        TransferChecked {
+            from: accounts.user_asset_account.to_account_info(),
+            mint: accounts.asset_mint.to_account_info(),
+            to: deposit.vault_token_account.to_account_info(),
+            authority: accounts.user.to_account_info(),
         },
-        signer_seeds,
     );
-    token::mint_to(mint_ctx, shares_to_mint)?;
-
-    // Emit event
-    emit!(Deposited {
-        vault: vault_state.key(),
-        user: ctx.accounts.user.key(),
-        asset_amount: amount,
-        shares_minted: shares_to_mint,
-        total_assets: vault_state.total_assets,
-        total_shares: vault_state.total_shares,
-        timestamp: Clock::get()?.unix_timestamp,
-    });
-
-    Ok(())
-}
-
+    token_interface::transfer_checked(transfer_ctx, amount, accounts.asset_mint.decimals)?;
 
+    deposit.credit_deposit(amount, balance_before, referrer)
+}
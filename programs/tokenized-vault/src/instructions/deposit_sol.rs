@@ -0,0 +1,206 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{self, Transfer},
+};
+use anchor_spl::{
+    token::spl_token,
+    token_2022::spl_token_2022,
+    token_interface::{
+        self, CloseAccount, Mint, SyncNative, TokenAccount, TokenInterface, TransferChecked,
+    },
+};
+
+use crate::{constants::*, errors::*, instructions::deposit::DepositAccounts, state::*};
+
+/// Deposit native SOL into a wSOL vault and receive shares
+#[derive(Accounts)]
+#[instruction(lamports: u64, proof: Vec<[u8; 32]>, referrer: Option<Pubkey>)]
+pub struct DepositSol<'info> {
+    /// User depositing SOL
+    /// Security: Must be signer, pays for and receives back the temporary account
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// Vault state PDA
+    /// Security: Validated by seeds, contains authority and totals
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.bump,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// Asset mint
+    /// Security: Must match vault_state.asset_mint and be a native SOL mint
+    #[account(
+        address = vault_state.asset_mint,
+        mint::token_program = asset_token_program,
+        constraint = asset_mint.key() == spl_token::native_mint::ID
+            || asset_mint.key() == spl_token_2022::native_mint::ID
+            @ VaultError::NotNativeMint,
+    )]
+    pub asset_mint: InterfaceAccount<'info, Mint>,
+
+    /// Share mint
+    /// Security: Must match vault_state.share_mint and share_token_program
+    #[account(
+        mut,
+        address = vault_state.share_mint,
+        mint::token_program = share_token_program,
+    )]
+    pub share_mint: InterfaceAccount<'info, Mint>,
+
+    /// Vault authority PDA
+    /// Security: CHECK constraint, validated by seeds
+    /// CHECK: PDA used as authority, validated by seeds
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.authority_bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Temporary wSOL account the lamports are wrapped into
+    /// Security: Fresh PDA owned by user, closed back to user in this instruction
+    #[account(
+        init,
+        payer = user,
+        seeds = [WSOL_TEMP_SEED, vault_state.key().as_ref(), user.key().as_ref()],
+        bump,
+        token::mint = asset_mint,
+        token::authority = user,
+        token::token_program = asset_token_program,
+    )]
+    pub user_wsol_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// User's share token account (destination)
+    /// Security: Must be owned by user and correct mint
+    #[account(
+        mut,
+        constraint = user_share_account.mint == vault_state.share_mint @ VaultError::InvalidMint,
+        constraint = user_share_account.owner == user.key() @ VaultError::InvalidOwner,
+    )]
+    pub user_share_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Vault-owned share escrow, receives the shares while the vault has a
+    /// lockup; required only then, and created on first use
+    /// Security: PDA token account whose authority is vault_authority
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [SHARE_ESCROW_SEED, vault_state.key().as_ref()],
+        bump,
+        token::mint = share_mint,
+        token::authority = vault_authority,
+        token::token_program = share_token_program,
+    )]
+    pub share_escrow: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// User's position PDA, created on first deposit
+    /// Security: Seeds bind it to this vault and user
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserPosition::SPACE,
+        seeds = [USER_POSITION_SEED, vault_state.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    /// Referrer's volume PDA, required when `referrer` is set
+    /// Security: Seeds bind it to this vault and the referrer argument
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = ReferralStats::SPACE,
+        seeds = [REFERRAL_SEED, vault_state.key().as_ref(), referrer.unwrap_or_default().as_ref()],
+        bump
+    )]
+    pub referral_stats: Option<Account<'info, ReferralStats>>,
+
+    /// Vault's token account
+    /// Security: Must be the vault_authority ATA, not another vault-owned account
+    #[account(
+        mut,
+        associated_token::mint = asset_mint,
+        associated_token::authority = vault_authority,
+        associated_token::token_program = asset_token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token program that owns the asset mint
+    pub asset_token_program: Interface<'info, TokenInterface>,
+
+    /// Token program that owns the share mint
+    pub share_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<DepositSol>,
+    lamports: u64,
+    proof: Vec<[u8; 32]>,
+    referrer: Option<Pubkey>,
+) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    let mut deposit = DepositAccounts {
+        user: &accounts.user,
+        vault_state: &mut accounts.vault_state,
+        share_mint: &accounts.share_mint,
+        vault_authority: &accounts.vault_authority,
+        user_share_account: &accounts.user_share_account,
+        share_escrow: accounts.share_escrow.as_ref(),
+        user_position: &mut accounts.user_position,
+        user_position_bump: ctx.bumps.user_position,
+        referral_stats: accounts.referral_stats.as_mut(),
+        referral_stats_bump: ctx.bumps.referral_stats,
+        vault_token_account: &mut accounts.vault_token_account,
+        share_token_program: &accounts.share_token_program,
+    };
+
+    // CHECKS: Validate amount, referrer, share escrow, allowlist and deposit caps
+    deposit.check_deposit(lamports, &proof, referrer)?;
+
+    // INTERACTIONS: Wrap the lamports into the temporary wSOL account
+    system_program::transfer(
+        CpiContext::new(
+            accounts.system_program.to_account_info(),
+            Transfer {
+                from: accounts.user.to_account_info(),
+                to: accounts.user_wsol_account.to_account_info(),
+            },
+        ),
+        lamports,
+    )?;
+    token_interface::sync_native(CpiContext::new(
+        accounts.asset_token_program.to_account_info(),
+        SyncNative {
+            account: accounts.user_wsol_account.to_account_info(),
+        },
+    ))?;
+
+    // Move the wrapped SOL into the vault, measuring what arrived as in `deposit`
+    let balance_before = deposit.vault_token_account.amount;
+
+    let transfer_ctx = CpiContext::new(
+        accounts.asset_token_program.to_account_info(),
+        TransferChecked {
+            from: accounts.user_wsol_account.to_account_info(),
+            mint: accounts.asset_mint.to_account_info(),
+            to: deposit.vault_token_account.to_account_info(),
+            authority: accounts.user.to_account_info(),
+        },
+    );
+    token_interface::transfer_checked(transfer_ctx, lamports, accounts.asset_mint.decimals)?;
+
+    // Return the temporary account's rent to the user
+    token_interface::close_account(CpiContext::new(
+        accounts.asset_token_program.to_account_info(),
+        CloseAccount {
+            account: accounts.user_wsol_account.to_account_info(),
+            destination: accounts.user.to_account_info(),
+            authority: accounts.user.to_account_info(),
+        },
+    ))?;
+
+    deposit.credit_deposit(lamports, balance_before, referrer)
+}
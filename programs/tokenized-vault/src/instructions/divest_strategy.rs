@@ -0,0 +1,175 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{adapter::AdapterAccounts, constants::*, errors::*, events::*, state::*};
+
+/// Withdraw vault assets from a whitelisted strategy adapter via CPI
+///
+/// Remaining accounts: adapter-specific accounts, passed through unchanged.
+#[derive(Accounts)]
+pub struct DivestStrategy<'info> {
+    /// Vault authority - only they can divest, also after shutdown, since the
+    /// caller chooses the adapter accounts
+    /// Security: Must be signer and match vault_state.authority
+    pub authority: Signer<'info>,
+
+    /// Vault state PDA
+    /// Security: has_one constraint validates authority from state
+    #[account(
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.bump,
+        has_one = authority @ VaultError::Unauthorized,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// Approved protocol entry PDA for the adapter program
+    /// Security: Seeds bind the entry to this vault and adapter_program
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED, vault_state.key().as_ref(), adapter_program.key().as_ref()],
+        bump = protocol.bump,
+        constraint = protocol.metadata.kind == ProtocolKind::Adapter @ VaultError::InvalidAdapter,
+    )]
+    pub protocol: Account<'info, ApprovedProtocol>,
+
+    /// Strategy adapter program
+    /// CHECK: Whitelisted through the protocol entry seeds
+    #[account(executable)]
+    pub adapter_program: UncheckedAccount<'info>,
+
+    /// Asset mint
+    /// Security: Must match vault_state.asset_mint and token_program
+    #[account(
+        address = vault_state.asset_mint,
+        mint::token_program = token_program,
+    )]
+    pub asset_mint: InterfaceAccount<'info, Mint>,
+
+    /// Vault authority PDA
+    /// Security: CHECK constraint, validated by seeds; never passed to the adapter
+    /// CHECK: PDA only identifies the vault token account, validated by seeds
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.authority_bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Vault's token account (destination)
+    /// Security: Must be the vault_authority ATA, not another vault-owned account
+    #[account(
+        mut,
+        associated_token::mint = asset_mint,
+        associated_token::authority = vault_authority,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Strategy authority PDA, the only signer the adapter receives
+    /// Security: CHECK constraint, validated by seeds
+    /// CHECK: PDA used as authority, validated by seeds
+    #[account(
+        seeds = [STRATEGY_SEED, vault_state.key().as_ref(), adapter_program.key().as_ref()],
+        bump,
+    )]
+    pub strategy_authority: UncheckedAccount<'info>,
+
+    /// Strategy token account, receives the withdrawal before it is swept to the vault
+    /// Security: Must be the strategy_authority ATA
+    #[account(
+        mut,
+        associated_token::mint = asset_mint,
+        associated_token::authority = strategy_authority,
+        associated_token::token_program = token_program,
+    )]
+    pub strategy_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token program that owns the asset mint
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DivestStrategy<'info>>,
+    amount: u64,
+) -> Result<()> {
+    // CHECKS: Gains must be booked with `report_strategy` before divesting them
+    require!(amount > 0, VaultError::ZeroWithdrawAmount);
+    require!(
+        amount <= ctx.accounts.protocol.invested_amount,
+        VaultError::DivestAmountTooLarge
+    );
+
+    // EFFECTS: Track divestment before the CPI
+    let protocol = &mut ctx.accounts.protocol;
+    protocol.track_divestment(amount)?;
+
+    // INTERACTIONS: CPI into the adapter, signed by the strategy authority only
+    let vault_key = ctx.accounts.vault_state.key();
+    let adapter_key = ctx.accounts.adapter_program.key();
+    let strategy_seeds: &[&[u8]] = &[
+        STRATEGY_SEED,
+        vault_key.as_ref(),
+        adapter_key.as_ref(),
+        &[ctx.bumps.strategy_authority],
+    ];
+    let signer_seeds = &[strategy_seeds];
+
+    let strategy_balance_before = ctx.accounts.strategy_token_account.amount;
+    let adapter = AdapterAccounts {
+        adapter_program: ctx.accounts.adapter_program.to_account_info(),
+        strategy_authority: ctx.accounts.strategy_authority.to_account_info(),
+        strategy_token_account: ctx.accounts.strategy_token_account.to_account_info(),
+        asset_mint: ctx.accounts.asset_mint.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        remaining_accounts: ctx.remaining_accounts,
+    };
+    let withdrawn = adapter.withdraw(amount, signer_seeds)?;
+
+    // Security: Exactly `amount` must have arrived in the strategy token account
+    ctx.accounts.strategy_token_account.reload()?;
+    require!(
+        withdrawn == amount
+            && ctx
+                .accounts
+                .strategy_token_account
+                .amount
+                .checked_sub(strategy_balance_before)
+                == Some(amount),
+        VaultError::InvalidAdapterReturn
+    );
+
+    // Sweep the withdrawal into the vault token account, which must rise by `amount`
+    let balance_before = ctx.accounts.vault_token_account.amount;
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.strategy_token_account.to_account_info(),
+            mint: ctx.accounts.asset_mint.to_account_info(),
+            to: ctx.accounts.vault_token_account.to_account_info(),
+            authority: ctx.accounts.strategy_authority.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token_interface::transfer_checked(transfer_ctx, amount, ctx.accounts.asset_mint.decimals)?;
+
+    ctx.accounts.vault_token_account.reload()?;
+    require!(
+        ctx.accounts
+            .vault_token_account
+            .amount
+            .checked_sub(balance_before)
+            == Some(amount),
+        VaultError::InvalidAdapterReturn
+    );
+
+    // Emit event
+    emit!(Divested {
+        vault: vault_key,
+        target: ctx.accounts.protocol.target,
+        authority: ctx.accounts.authority.key(),
+        amount,
+        invested_amount: ctx.accounts.protocol.invested_amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
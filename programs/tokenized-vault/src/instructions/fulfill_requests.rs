@@ -0,0 +1,162 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_interface::{self, Burn, Mint, TokenAccount, TokenInterface};
+
+use crate::{constants::*, errors::*, events::*, state::*};
+
+/// Fulfill a batch of pending redeem requests at one exchange rate
+///
+/// Remaining accounts: the `RedeemRequest` PDAs to fulfill, all writable.
+#[derive(Accounts)]
+pub struct FulfillRequests<'info> {
+    /// Vault authority (allocator); anyone once the vault is shut down
+    /// Security: Must be signer and match vault_state.authority while active
+    pub authority: Signer<'info>,
+
+    /// Vault state PDA
+    /// Security: Validated by seeds; authority checked unless shut down
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.bump,
+        constraint = vault_state.is_shutdown || vault_state.authority == authority.key()
+            @ VaultError::Unauthorized,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// Share mint
+    /// Security: Must match vault_state.share_mint and share_token_program
+    #[account(
+        mut,
+        address = vault_state.share_mint,
+        mint::token_program = share_token_program,
+    )]
+    pub share_mint: InterfaceAccount<'info, Mint>,
+
+    /// Vault authority PDA
+    /// Security: CHECK constraint, validated by seeds
+    /// CHECK: PDA used as escrow authority, validated by seeds
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.authority_bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Vault-owned share escrow
+    /// Security: Validated by seeds
+    #[account(
+        mut,
+        seeds = [SHARE_ESCROW_SEED, vault_state.key().as_ref()],
+        bump,
+    )]
+    pub share_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    /// Vault's token account (idle assets backing the claims)
+    /// Security: Must be the vault_authority ATA, not another vault-owned account
+    #[account(
+        address = get_associated_token_address_with_program_id(
+            &vault_authority.key(),
+            &vault_state.asset_mint,
+            vault_token_account.to_account_info().owner,
+        ) @ VaultError::InvalidOwner,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token program that owns the share mint
+    pub share_token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, FulfillRequests<'info>>) -> Result<()> {
+    // CHECKS: Validate the batch of requests
+    require!(
+        !ctx.remaining_accounts.is_empty(),
+        VaultError::InvalidRedeemRequest
+    );
+
+    let vault_key = ctx.accounts.vault_state.key();
+    let mut requests: Vec<Account<'info, RedeemRequest>> =
+        Vec::with_capacity(ctx.remaining_accounts.len());
+    for info in ctx.remaining_accounts.iter() {
+        // Security: try_from checks program ownership and account layout
+        let request: Account<'info, RedeemRequest> = Account::try_from(info)?;
+        require!(
+            request.vault == vault_key && !request.is_fulfilled(),
+            VaultError::InvalidRedeemRequest
+        );
+        require!(
+            !requests.iter().any(|r| r.key() == request.key()),
+            VaultError::InvalidRedeemRequest
+        );
+        requests.push(request);
+    }
+
+    // Lock in the exchange rate before any request of the batch is applied
+    let vault_state = &mut ctx.accounts.vault_state;
+    let now = Clock::get()?.unix_timestamp;
+    let mut batch_shares: u64 = 0;
+    let mut batch_assets: u64 = 0;
+    for request in requests.iter_mut() {
+        let assets = vault_state.calculate_assets(request.shares)?;
+        batch_shares = batch_shares
+            .checked_add(request.shares)
+            .ok_or(VaultError::MathOverflow)?;
+        batch_assets = batch_assets
+            .checked_add(assets)
+            .ok_or(VaultError::MathOverflow)?;
+        request.assets = assets;
+        request.fulfilled_at = now;
+    }
+
+    // Claims are paid from idle assets; divest first if needed
+    require!(
+        vault_state.idle_assets(ctx.accounts.vault_token_account.amount) >= batch_assets,
+        VaultError::InsufficientLiquidity
+    );
+
+    // EFFECTS: Burn-side accounting and claim reservation before the burn
+    vault_state.settle_redeems(batch_shares, batch_assets)?;
+
+    // INTERACTIONS: Burn the escrowed shares, signed by vault_authority
+    let asset_mint_key = vault_state.asset_mint;
+    let authority_bump = vault_state.authority_bump;
+    let id_seed = vault_id_seed(vault_state.vault_id);
+    let authority_seeds: &[&[u8]] = &[
+        VAULT_AUTHORITY_SEED,
+        asset_mint_key.as_ref(),
+        &id_seed,
+        &[authority_bump],
+    ];
+    let signer_seeds = &[authority_seeds];
+
+    token_interface::burn(
+        CpiContext::new_with_signer(
+            ctx.accounts.share_token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.share_mint.to_account_info(),
+                from: ctx.accounts.share_escrow.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        batch_shares,
+    )?;
+
+    // Persist updated requests (not handled by Anchor for remaining accounts)
+    for request in requests.iter() {
+        request.exit(ctx.program_id)?;
+    }
+
+    // Emit event
+    emit!(RedeemsFulfilled {
+        vault: vault_key,
+        request_count: requests.len() as u32,
+        shares_burned: batch_shares,
+        assets: batch_assets,
+        total_assets: vault_state.total_assets,
+        total_shares: vault_state.total_shares,
+        claimable_assets: vault_state.claimable_assets,
+        timestamp: now,
+    });
+
+    Ok(())
+}
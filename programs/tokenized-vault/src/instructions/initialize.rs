@@ -1,13 +1,22 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{
+    prelude::*,
+    solana_program::program_pack::Pack,
+    system_program::{self, Allocate, Assign, CreateAccount, Transfer},
+};
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint, Token, TokenAccount},
+    token_2022::spl_token_2022::{extension::ExtensionType, state::Mint as SplMint},
+    token_interface::{
+        self, InitializeMint2, MetadataPointerInitialize, Mint, TokenAccount, TokenInterface,
+        TokenMetadataInitialize,
+    },
 };
 
-use crate::{constants::*, events::*, state::*};
+use crate::{constants::*, errors::*, events::*, state::*};
 
 /// Initialize a new vault for a given asset token
 #[derive(Accounts)]
+#[instruction(vault_id: u64)]
 pub struct Initialize<'info> {
     /// Vault authority - can invest vault assets
     /// Security: Must be signer, stored in state
@@ -20,32 +29,66 @@ pub struct Initialize<'info> {
         init,
         payer = authority,
         space = VAULT_STATE_SIZE,
-        seeds = [VAULT_SEED, asset_mint.key().as_ref()],
+        seeds = [VAULT_SEED, asset_mint.key().as_ref(), &vault_id_seed(vault_id)],
         bump
     )]
     pub vault_state: Account<'info, VaultState>,
 
     /// Asset token mint (the underlying token users deposit)
-    /// Security: No constraints needed - any valid mint can have a vault
-    pub asset_mint: Account<'info, Mint>,
+    /// Security: Any valid SPL Token or Token-2022 mint can have a vault
+    #[account(mint::token_program = asset_token_program)]
+    pub asset_mint: InterfaceAccount<'info, Mint>,
 
-    /// Share token mint PDA (vault shares)
-    /// Security: Mint authority is vault_authority PDA
+    /// Program config PDA
+    /// Security: Source of the creation fee and the next vault index
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    /// Allowlist entry for the asset mint
+    /// Security: Vaults can only be created for mints the admin allowed
+    #[account(
+        seeds = [ALLOWED_MINT_SEED, asset_mint.key().as_ref()],
+        bump = allowed_mint.bump,
+        constraint = allowed_mint.allowed @ VaultError::MintNotAllowed,
+    )]
+    pub allowed_mint: Box<Account<'info, AllowedMint>>,
+
+    /// Vault index entry PDA for this vault
+    /// Security: Seeded by the config's vault count, so entries are contiguous
     #[account(
         init,
         payer = authority,
-        seeds = [SHARE_MINT_SEED, asset_mint.key().as_ref()],
+        space = VaultIndexEntry::SPACE,
+        seeds = [VAULT_INDEX_SEED, &config.vault_count.to_le_bytes()],
+        bump
+    )]
+    pub vault_index_entry: Box<Account<'info, VaultIndexEntry>>,
+
+    /// Receives the creation fee
+    /// CHECK: Only receives lamports, validated against config
+    #[account(mut, address = config.fee_recipient)]
+    pub fee_recipient: UncheckedAccount<'info>,
+
+    /// Share token mint PDA (vault shares)
+    /// Security: Created in the handler under share_token_program, with
+    /// vault_authority as mint authority (and metadata authority if set)
+    /// CHECK: Uninitialized PDA, validated by seeds and created by this instruction
+    #[account(
+        mut,
+        seeds = [SHARE_MINT_SEED, asset_mint.key().as_ref(), &vault_id_seed(vault_id)],
         bump,
-        mint::decimals = asset_mint.decimals,
-        mint::authority = vault_authority,
     )]
-    pub share_mint: Account<'info, Mint>,
+    pub share_mint: UncheckedAccount<'info>,
 
     /// Vault authority PDA - used as mint authority for shares
     /// Security: CHECK constraint ensures correct derivation
     /// CHECK: PDA used as mint authority, validated by seeds
     #[account(
-        seeds = [VAULT_AUTHORITY_SEED, asset_mint.key().as_ref()],
+        seeds = [VAULT_AUTHORITY_SEED, asset_mint.key().as_ref(), &vault_id_seed(vault_id)],
         bump
     )]
     pub vault_authority: UncheckedAccount<'info>,
@@ -57,15 +100,63 @@ pub struct Initialize<'info> {
         payer = authority,
         associated_token::mint = asset_mint,
         associated_token::authority = vault_authority,
+        associated_token::token_program = asset_token_program,
     )]
-    pub vault_token_account: Account<'info, TokenAccount>,
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
 
-    pub token_program: Program<'info, Token>,
+    /// Token program that owns the asset mint (SPL Token or Token-2022)
+    pub asset_token_program: Interface<'info, TokenInterface>,
+
+    /// Token program the share mint is created under (SPL Token or Token-2022)
+    pub share_token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<Initialize>) -> Result<()> {
+pub fn handler(
+    ctx: Context<Initialize>,
+    vault_id: u64,
+    share_metadata: Option<ShareMetadata>,
+) -> Result<()> {
+    // CHECKS: Metadata lives in a Token-2022 extension
+    if let Some(metadata) = &share_metadata {
+        metadata.validate()?;
+        require_keys_eq!(
+            ctx.accounts.share_token_program.key(),
+            anchor_spl::token_2022::ID,
+            VaultError::ShareMetadataRequiresToken2022
+        );
+    }
+
+    // INTERACTIONS: Pay the creation fee
+    let creation_fee_lamports = ctx.accounts.config.creation_fee_lamports;
+    if creation_fee_lamports > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: ctx.accounts.fee_recipient.to_account_info(),
+                },
+            ),
+            creation_fee_lamports,
+        )?;
+    }
+
+    // Create the share mint (and its metadata) under share_token_program
+    create_share_mint(&ctx, vault_id, share_metadata.as_ref())?;
+
+    // EFFECTS: Append the vault to the program's vault index
+    let index = ctx.accounts.config.next_vault_index()?;
+    let vault_index_entry = &mut ctx.accounts.vault_index_entry;
+    vault_index_entry.index = index;
+    vault_index_entry.vault = ctx.accounts.vault_state.key();
+    vault_index_entry.asset_mint = ctx.accounts.asset_mint.key();
+    vault_index_entry.vault_id = vault_id;
+    vault_index_entry.authority = ctx.accounts.authority.key();
+    vault_index_entry.created_at = Clock::get()?.unix_timestamp;
+    vault_index_entry.bump = ctx.bumps.vault_index_entry;
+
     let vault_state = &mut ctx.accounts.vault_state;
 
     // EFFECTS: Initialize vault state
@@ -77,20 +168,185 @@ pub fn handler(ctx: Context<Initialize>) -> Result<()> {
     vault_state.bump = ctx.bumps.vault_state;
     vault_state.share_bump = ctx.bumps.share_mint;
     vault_state.authority_bump = ctx.bumps.vault_authority;
-    vault_state._reserved = [0; 128];
+    vault_state.vault_id = vault_id;
+    vault_state.deposit_cap = 0;
+    vault_state.per_user_deposit_cap = 0;
+    vault_state.depositor_merkle_root = [0; 32];
+    vault_state.lockup_duration = 0;
+    vault_state.pending_redeem_shares = 0;
+    vault_state.claimable_assets = 0;
+    vault_state.min_idle_bps = 0;
+    vault_state.referral_fee_bps = 0;
+    vault_state.is_shutdown = false;
+    vault_state.shutdown_reason = 0;
+    vault_state.version = VAULT_STATE_VERSION;
+    vault_state.indexed = true;
+    vault_state._reserved = [0; 40];
 
     // INTERACTIONS: Emit event
     emit!(VaultInitialized {
         vault: vault_state.key(),
         authority: vault_state.authority,
         asset_mint: vault_state.asset_mint,
+        vault_id,
         share_mint: vault_state.share_mint,
+        index,
+        creation_fee_lamports,
         timestamp: Clock::get()?.unix_timestamp,
     });
 
-   // Vault initialized successfully
+    if let Some(metadata) = share_metadata {
+        emit!(ShareMetadataUpdated {
+            vault: vault_state.key(),
+            share_mint: vault_state.share_mint,
+            name: metadata.name,
+            symbol: metadata.symbol,
+            uri: metadata.uri,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+    }
+
+    // Vault initialized successfully
 
     Ok(())
 }
 
+/// Create the share mint PDA, with the Token-2022 metadata pointer and metadata
+/// extensions when `share_metadata` is set
+///
+/// Security: Share decimals match the asset; vault_authority is mint authority,
+/// metadata update authority, and there is no freeze authority
+fn create_share_mint(
+    ctx: &Context<Initialize>,
+    vault_id: u64,
+    share_metadata: Option<&ShareMetadata>,
+) -> Result<()> {
+    let share_mint = ctx.accounts.share_mint.to_account_info();
+    let vault_authority = ctx.accounts.vault_authority.key();
+    let share_token_program = ctx.accounts.share_token_program.to_account_info();
+
+    let asset_mint_key = ctx.accounts.asset_mint.key();
+    let id_seed = vault_id_seed(vault_id);
+    let share_seeds: &[&[u8]] = &[
+        SHARE_MINT_SEED,
+        asset_mint_key.as_ref(),
+        &id_seed,
+        &[ctx.bumps.share_mint],
+    ];
+    let authority_seeds: &[&[u8]] = &[
+        VAULT_AUTHORITY_SEED,
+        asset_mint_key.as_ref(),
+        &id_seed,
+        &[ctx.bumps.vault_authority],
+    ];
 
+    // Token-2022 reallocs the mint for the metadata itself, but the rent for
+    // that space must already be in the account
+    let (space, metadata_len) = match share_metadata {
+        Some(metadata) => (
+            ExtensionType::try_calculate_account_len::<SplMint>(&[ExtensionType::MetadataPointer])?,
+            metadata.tlv_size_of(&share_mint.key(), &vault_authority)?,
+        ),
+        None => (SplMint::LEN, 0),
+    };
+    let lamports = Rent::get()?.minimum_balance(space + metadata_len);
+
+    // Mirrors Anchor's `init`: a pre-funded PDA cannot block creation
+    let current_lamports = share_mint.lamports();
+    if current_lamports == 0 {
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                CreateAccount {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: share_mint.clone(),
+                },
+                &[share_seeds],
+            ),
+            lamports,
+            space as u64,
+            share_token_program.key,
+        )?;
+    } else {
+        if current_lamports < lamports {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: share_mint.clone(),
+                    },
+                ),
+                lamports - current_lamports,
+            )?;
+        }
+        system_program::allocate(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                Allocate {
+                    account_to_allocate: share_mint.clone(),
+                },
+                &[share_seeds],
+            ),
+            space as u64,
+        )?;
+        system_program::assign(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                Assign {
+                    account_to_assign: share_mint.clone(),
+                },
+                &[share_seeds],
+            ),
+            share_token_program.key,
+        )?;
+    }
+
+    // Extensions must be initialized before the mint itself
+    if share_metadata.is_some() {
+        token_interface::metadata_pointer_initialize(
+            CpiContext::new(
+                share_token_program.clone(),
+                MetadataPointerInitialize {
+                    token_program_id: share_token_program.clone(),
+                    mint: share_mint.clone(),
+                },
+            ),
+            Some(vault_authority),
+            Some(share_mint.key()),
+        )?;
+    }
+
+    token_interface::initialize_mint2(
+        CpiContext::new(
+            share_token_program.clone(),
+            InitializeMint2 {
+                mint: share_mint.clone(),
+            },
+        ),
+        ctx.accounts.asset_mint.decimals,
+        &vault_authority,
+        None,
+    )?;
+
+    if let Some(metadata) = share_metadata {
+        token_interface::token_metadata_initialize(
+            CpiContext::new_with_signer(
+                share_token_program.clone(),
+                TokenMetadataInitialize {
+                    program_id: share_token_program.clone(),
+                    metadata: share_mint.clone(),
+                    update_authority: ctx.accounts.vault_authority.to_account_info(),
+                    mint_authority: ctx.accounts.vault_authority.to_account_info(),
+                    mint: share_mint.clone(),
+                },
+                &[authority_seeds],
+            ),
+            metadata.name.clone(),
+            metadata.symbol.clone(),
+            metadata.uri.clone(),
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::*, errors::*, events::*, program::TokenizedVault, state::*};
+
+/// Create the program-wide config
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    /// Program upgrade authority - becomes the config admin
+    /// Security: Must be signer and match the program data account
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Config PDA (singleton)
+    /// Security: `init` prevents re-initialization
+    #[account(
+        init,
+        payer = admin,
+        space = GlobalConfig::SPACE,
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    /// This program
+    /// Security: Ties program_data to this program
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, TokenizedVault>,
+
+    /// Program data account
+    /// Security: Only the upgrade authority can create the config
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ VaultError::Unauthorized,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<InitializeConfig>, params: ConfigParams) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    // EFFECTS: Initialize config
+    config.admin = ctx.accounts.admin.key();
+    config.apply(&params);
+    config.vault_count = 0;
+    config.bump = ctx.bumps.config;
+    config._reserved = [0; 64];
+
+    // Emit event
+    emit!(ConfigUpdated {
+        admin: config.admin,
+        fee_recipient: config.fee_recipient,
+        creation_fee_lamports: config.creation_fee_lamports,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
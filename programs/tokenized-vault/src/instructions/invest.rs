@@ -1,14 +1,14 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
 
 use crate::{constants::*, errors::*, events::*, state::*};
 
 /// Invest vault assets into a whitelisted protocol via CPI
 ///
 /// Architecture: Registry + Whitelist (Option 3)
-/// - Validates target against on-chain protocol registry
+/// - Validates target against its on-chain `ApprovedProtocol` entry
 /// - Prevents authority from investing to arbitrary addresses
-/// - Tracks invested amount per protocol
+/// - Tracks invested amount per protocol (and enforces its cap)
 ///
 #[derive(Accounts)]
 pub struct Invest<'info> {
@@ -21,48 +21,61 @@ pub struct Invest<'info> {
     /// Security: has_one constraint validates authority from state
     #[account(
         mut,
-        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref()],
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
         bump = vault_state.bump,
         has_one = authority @ VaultError::Unauthorized,
     )]
     pub vault_state: Account<'info, VaultState>,
 
-    /// Protocol registry PDA
-    /// Security: Validates target against whitelist
+    /// Approved protocol entry PDA for the target
+    /// Security: Seeds bind the entry to this vault and target_token_account,
+    /// so a non-whitelisted target has no entry to load
     #[account(
         mut,
-        seeds = [b"protocol_registry", vault_state.key().as_ref()],
-        bump = protocol_registry.bump,
+        seeds = [PROTOCOL_SEED, vault_state.key().as_ref(), target_token_account.key().as_ref()],
+        bump = protocol.bump,
     )]
-    pub protocol_registry: Account<'info, ProtocolRegistry>,
+    pub protocol: Account<'info, ApprovedProtocol>,
+
+    /// Asset mint
+    /// Security: Must match vault_state.asset_mint and token_program
+    #[account(
+        address = vault_state.asset_mint,
+        mint::token_program = token_program,
+    )]
+    pub asset_mint: InterfaceAccount<'info, Mint>,
 
     /// Vault authority PDA
     /// Security: CHECK constraint, validated by seeds
     /// CHECK: PDA used as authority, validated by seeds
     #[account(
-        seeds = [VAULT_AUTHORITY_SEED, vault_state.asset_mint.as_ref()],
+        seeds = [VAULT_AUTHORITY_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
         bump = vault_state.authority_bump,
     )]
     pub vault_authority: UncheckedAccount<'info>,
 
     /// Vault's token account (source of investment)
-    /// Security: Must be correct mint and owned by vault_authority
+    /// Security: Must be the vault_authority ATA, not another vault-owned account
     #[account(
         mut,
-        constraint = vault_token_account.mint == vault_state.asset_mint @ VaultError::InvalidMint,
-        constraint = vault_token_account.owner == vault_authority.key() @ VaultError::InvalidOwner,
+        associated_token::mint = asset_mint,
+        associated_token::authority = vault_authority,
+        associated_token::token_program = token_program,
     )]
-    pub vault_token_account: Account<'info, TokenAccount>,
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
 
     /// Target token account (destination for investment)
-    /// Security: Must be correct mint, validated against whitelist
+    /// Security: Must be correct mint, validated against whitelist entry, and still
+    /// owned by the approved owner (SPL owners can be changed with SetAuthority)
     #[account(
         mut,
         constraint = target_token_account.mint == vault_state.asset_mint @ VaultError::InvalidMint,
+        constraint = target_token_account.owner == protocol.expected_owner @ VaultError::TargetOwnerMismatch,
     )]
-    pub target_token_account: Account<'info, TokenAccount>,
+    pub target_token_account: InterfaceAccount<'info, TokenAccount>,
 
-    pub token_program: Program<'info, Token>,
+    /// Token program that owns the asset mint
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 pub fn handler(ctx: Context<Invest>, amount: u64) -> Result<()> {
@@ -70,17 +83,15 @@ pub fn handler(ctx: Context<Invest>, amount: u64) -> Result<()> {
     require!(amount > 0, VaultError::ZeroInvestAmount);
 
     let vault_state = &ctx.accounts.vault_state;
-    let registry = &mut ctx.accounts.protocol_registry;
+    let protocol = &mut ctx.accounts.protocol;
     let target = ctx.accounts.target_token_account.key();
 
-    // CRITICAL SECURITY CHECK: Validate target is in whitelist
-    require!(
-        registry.is_protocol_approved(&target),
-        VaultError::ProtocolNotApproved
-    );
+    // CRITICAL SECURITY CHECK: Validate target is whitelisted and enabled
+    vault_state.check_active()?;
+    require!(protocol.enabled, VaultError::ProtocolNotApproved);
 
-    // Verify vault has enough assets
-    let available_balance = ctx.accounts.vault_token_account.amount;
+    // Verify vault has enough assets not owed to redeem claims
+    let available_balance = vault_state.idle_assets(ctx.accounts.vault_token_account.amount);
     require!(
         available_balance >= amount,
         VaultError::InsufficientVaultBalance
@@ -92,37 +103,42 @@ pub fn handler(ctx: Context<Invest>, amount: u64) -> Result<()> {
         VaultError::InvestAmountTooLarge
     );
 
-    // Get protocol name for event
-    let protocol_name = registry
-        .get_protocol_mut(&target)
-        .map(|p| p.name.clone())
-        .unwrap_or_else(|| "Unknown".to_string());
+    // Keep the idle buffer for withdrawals and redeem fulfillment
+    vault_state.check_idle_buffer(ctx.accounts.vault_token_account.amount, amount)?;
+
+    // Get protocol labels for event
+    let protocol_name = protocol.name.clone();
+    let protocol_kind = protocol.metadata.kind;
+    let risk_tier = protocol.metadata.risk_tier;
 
-    // EFFECTS: Track investment in registry
-    registry.track_investment(&target, amount)?;
+    // EFFECTS: Track investment on the protocol entry
+    protocol.track_investment(amount)?;
 
     // INTERACTIONS: Perform CPI to transfer assets
 
     let asset_mint_key = vault_state.asset_mint;
     let authority_bump = vault_state.authority_bump;
+    let id_seed = vault_id_seed(vault_state.vault_id);
     let authority_seeds: &[&[u8]] = &[
         VAULT_AUTHORITY_SEED,
         asset_mint_key.as_ref(),
+        &id_seed,
         &[authority_bump],
     ];
-    let signer_seeds = &[&authority_seeds[..]];
+    let signer_seeds = &[authority_seeds];
 
     // Transfer from vault to target
     let transfer_ctx = CpiContext::new_with_signer(
         ctx.accounts.token_program.to_account_info(),
-        Transfer {
+        TransferChecked {
             from: ctx.accounts.vault_token_account.to_account_info(),
+            mint: ctx.accounts.asset_mint.to_account_info(),
             to: ctx.accounts.target_token_account.to_account_info(),
             authority: ctx.accounts.vault_authority.to_account_info(),
         },
         signer_seeds,
     );
-    token::transfer(transfer_ctx, amount)?;
+    token_interface::transfer_checked(transfer_ctx, amount, ctx.accounts.asset_mint.decimals)?;
 
     // Emit event for tracking
     emit!(Invested {
@@ -130,6 +146,8 @@ pub fn handler(ctx: Context<Invest>, amount: u64) -> Result<()> {
         authority: ctx.accounts.authority.key(),
         target,
         protocol_name,
+        protocol_kind,
+        risk_tier,
         amount,
         total_assets: vault_state.total_assets,
         timestamp: Clock::get()?.unix_timestamp,
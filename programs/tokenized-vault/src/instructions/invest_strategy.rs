@@ -0,0 +1,195 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{adapter::AdapterAccounts, constants::*, errors::*, events::*, state::*};
+
+/// Invest vault assets into a whitelisted strategy adapter via CPI
+///
+/// Remaining accounts: adapter-specific accounts, passed through unchanged.
+#[derive(Accounts)]
+pub struct InvestStrategy<'info> {
+    /// Vault authority - only they can invest
+    /// Security: Must be signer and match vault_state.authority; pays for the
+    /// strategy token account on first use
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Vault state PDA
+    /// Security: has_one constraint validates authority from state
+    #[account(
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.bump,
+        has_one = authority @ VaultError::Unauthorized,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// Approved protocol entry PDA for the adapter program
+    /// Security: Seeds bind the entry to this vault and adapter_program, so a
+    /// non-whitelisted adapter has no entry to load
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED, vault_state.key().as_ref(), adapter_program.key().as_ref()],
+        bump = protocol.bump,
+        constraint = protocol.metadata.kind == ProtocolKind::Adapter @ VaultError::InvalidAdapter,
+    )]
+    pub protocol: Account<'info, ApprovedProtocol>,
+
+    /// Strategy adapter program
+    /// CHECK: Whitelisted through the protocol entry seeds
+    #[account(executable)]
+    pub adapter_program: UncheckedAccount<'info>,
+
+    /// Asset mint
+    /// Security: Must match vault_state.asset_mint and token_program
+    #[account(
+        address = vault_state.asset_mint,
+        mint::token_program = token_program,
+    )]
+    pub asset_mint: InterfaceAccount<'info, Mint>,
+
+    /// Vault authority PDA
+    /// Security: CHECK constraint, validated by seeds
+    /// CHECK: PDA used as authority, validated by seeds
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.authority_bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Vault's token account (source of investment)
+    /// Security: Must be the vault_authority ATA, not another vault-owned account
+    #[account(
+        mut,
+        associated_token::mint = asset_mint,
+        associated_token::authority = vault_authority,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Strategy authority PDA, the only signer the adapter receives
+    /// Security: CHECK constraint, validated by seeds; owns nothing but this
+    /// adapter's strategy token account and position
+    /// CHECK: PDA used as authority, validated by seeds
+    #[account(
+        seeds = [STRATEGY_SEED, vault_state.key().as_ref(), adapter_program.key().as_ref()],
+        bump,
+    )]
+    pub strategy_authority: UncheckedAccount<'info>,
+
+    /// Strategy token account, funded with exactly `amount` for the adapter
+    /// Security: Must be the strategy_authority ATA
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = asset_mint,
+        associated_token::authority = strategy_authority,
+        associated_token::token_program = token_program,
+    )]
+    pub strategy_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token program that owns the asset mint
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, InvestStrategy<'info>>,
+    amount: u64,
+) -> Result<()> {
+    // CHECKS: Same limits as `invest`
+    require!(amount > 0, VaultError::ZeroInvestAmount);
+
+    let vault_state = &ctx.accounts.vault_state;
+    vault_state.check_active()?;
+    require!(
+        ctx.accounts.protocol.enabled,
+        VaultError::ProtocolNotApproved
+    );
+
+    let balance_before = ctx.accounts.vault_token_account.amount;
+    require!(
+        vault_state.idle_assets(balance_before) >= amount,
+        VaultError::InsufficientVaultBalance
+    );
+    require!(
+        amount <= vault_state.total_assets,
+        VaultError::InvestAmountTooLarge
+    );
+    vault_state.check_idle_buffer(balance_before, amount)?;
+
+    // EFFECTS: Track investment on the protocol entry
+    let protocol = &mut ctx.accounts.protocol;
+    protocol.track_investment(amount)?;
+
+    // INTERACTIONS: Fund the strategy token account, signed by vault_authority
+    let asset_mint_key = vault_state.asset_mint;
+    let authority_bump = vault_state.authority_bump;
+    let id_seed = vault_id_seed(vault_state.vault_id);
+    let authority_seeds: &[&[u8]] = &[
+        VAULT_AUTHORITY_SEED,
+        asset_mint_key.as_ref(),
+        &id_seed,
+        &[authority_bump],
+    ];
+    let signer_seeds = &[authority_seeds];
+
+    let strategy_balance_before = ctx.accounts.strategy_token_account.amount;
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.vault_token_account.to_account_info(),
+            mint: ctx.accounts.asset_mint.to_account_info(),
+            to: ctx.accounts.strategy_token_account.to_account_info(),
+            authority: ctx.accounts.vault_authority.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token_interface::transfer_checked(transfer_ctx, amount, ctx.accounts.asset_mint.decimals)?;
+
+    // CPI into the adapter, signed by the strategy authority only
+    let vault_key = vault_state.key();
+    let adapter_key = ctx.accounts.adapter_program.key();
+    let strategy_seeds: &[&[u8]] = &[
+        STRATEGY_SEED,
+        vault_key.as_ref(),
+        adapter_key.as_ref(),
+        &[ctx.bumps.strategy_authority],
+    ];
+
+    let adapter = AdapterAccounts {
+        adapter_program: ctx.accounts.adapter_program.to_account_info(),
+        strategy_authority: ctx.accounts.strategy_authority.to_account_info(),
+        strategy_token_account: ctx.accounts.strategy_token_account.to_account_info(),
+        asset_mint: ctx.accounts.asset_mint.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        remaining_accounts: ctx.remaining_accounts,
+    };
+    let deposited = adapter.deposit(amount, &[strategy_seeds])?;
+
+    // Security: The adapter must have taken exactly the `amount` it was given
+    ctx.accounts.strategy_token_account.reload()?;
+    require!(
+        deposited == amount && ctx.accounts.strategy_token_account.amount == strategy_balance_before,
+        VaultError::InvalidAdapterReturn
+    );
+
+    // Emit event for tracking
+    let protocol = &ctx.accounts.protocol;
+    emit!(Invested {
+        vault: vault_state.key(),
+        authority: ctx.accounts.authority.key(),
+        target: protocol.target,
+        protocol_name: protocol.name.clone(),
+        protocol_kind: protocol.metadata.kind,
+        risk_tier: protocol.metadata.risk_tier,
+        amount,
+        total_assets: vault_state.total_assets,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
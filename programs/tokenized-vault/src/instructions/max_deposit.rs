@@ -0,0 +1,18 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::*, state::*};
+
+/// Read-only view of the remaining deposit headroom
+#[derive(Accounts)]
+pub struct MaxDeposit<'info> {
+    /// Vault state PDA
+    #[account(
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.bump,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+pub fn handler(ctx: Context<MaxDeposit>) -> Result<u64> {
+    Ok(ctx.accounts.vault_state.max_deposit())
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::{constants::*, errors::*, state::*};
+
+/// Read-only view of how much `invest` can move out under the idle buffer
+#[derive(Accounts)]
+pub struct MaxInvest<'info> {
+    /// Vault state PDA
+    #[account(
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.bump,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// Vault authority PDA
+    /// CHECK: PDA used as token account owner, validated by seeds
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.authority_bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Vault's token account (idle assets)
+    #[account(
+        address = get_associated_token_address_with_program_id(
+            &vault_authority.key(),
+            &vault_state.asset_mint,
+            vault_token_account.to_account_info().owner,
+        ) @ VaultError::InvalidOwner,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+}
+
+pub fn handler(ctx: Context<MaxInvest>) -> Result<u64> {
+    ctx.accounts
+        .vault_state
+        .max_invest(ctx.accounts.vault_token_account.amount)
+}
@@ -0,0 +1,294 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Allocate, Assign, CreateAccount, Transfer};
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::{constants::*, errors::*, events::*, state::*};
+
+/// Upgrade a vault and its protocol registry to the current account layouts
+///
+/// Remaining accounts: only for registries on the legacy inline layout, one
+/// `(protocol, target_token_account)` pair per legacy entry, in registry order.
+/// `protocol` is the uncreated `ApprovedProtocol` PDA and must be writable.
+/// A target that is no longer a token account (e.g. closed) becomes a disabled
+/// entry with no owner pin, which `write_off_protocol` can clear.
+#[derive(Accounts)]
+pub struct MigrateVault<'info> {
+    /// Vault authority - only they can migrate the vault
+    /// Security: Checked against the stored authority; pays for any growth
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Vault state PDA on any layout version
+    /// Security: Program-owned; authority and seeds are checked in the handler
+    /// CHECK: Older layouts may be shorter than the current VaultState
+    #[account(mut, owner = crate::ID @ VaultError::InvalidOwner)]
+    pub vault_state: UncheckedAccount<'info>,
+
+    /// Protocol registry PDA, upgraded if it was ever created
+    /// Security: Validated by seeds; deserialized in the handler when present
+    /// CHECK: May not exist, or may still use the legacy inline layout
+    #[account(
+        mut,
+        seeds = [b"protocol_registry", vault_state.key().as_ref()],
+        bump,
+    )]
+    pub protocol_registry: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, MigrateVault<'info>>) -> Result<()> {
+    let vault_info = ctx.accounts.vault_state.to_account_info();
+    let vault_key = vault_info.key();
+
+    // CHECKS: Authority and PDA, since Anchor cannot deserialize older layouts
+    let mut vault_state = VaultState::try_deserialize_any_version(&vault_info.try_borrow_data()?)?;
+    require_keys_eq!(
+        vault_state.authority,
+        ctx.accounts.authority.key(),
+        VaultError::Unauthorized
+    );
+    let id_seed = vault_id_seed(vault_state.vault_id);
+    let expected_vault = Pubkey::create_program_address(
+        &[
+            VAULT_SEED,
+            vault_state.asset_mint.as_ref(),
+            &id_seed,
+            &[vault_state.bump],
+        ],
+        ctx.program_id,
+    )
+    .map_err(|_| VaultError::InvalidMigrationAccounts)?;
+    require_keys_eq!(
+        expected_vault,
+        vault_key,
+        VaultError::InvalidMigrationAccounts
+    );
+
+    let from_version = vault_state.migrate()?;
+
+    // EFFECTS: Grow the vault to the current size, then write the upgraded layout
+    resize_account(
+        &vault_info,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        VAULT_STATE_SIZE,
+    )?;
+    vault_state.try_serialize(&mut &mut vault_info.try_borrow_mut_data()?[..])?;
+
+    let registry_info = ctx.accounts.protocol_registry.to_account_info();
+    let registry_migrated = !registry_info.data_is_empty();
+    let mut protocols_created: u32 = 0;
+    if registry_migrated {
+        require_keys_eq!(*registry_info.owner, crate::ID, VaultError::InvalidOwner);
+
+        let registry = if registry_info.data_len() == LEGACY_PROTOCOL_REGISTRY_SPACE {
+            let legacy =
+                LegacyProtocolRegistry::try_from_account_data(&registry_info.try_borrow_data()?)?;
+            protocols_created = create_legacy_protocols(&ctx, &legacy)?;
+            ProtocolRegistry::from_legacy(&legacy)?
+        } else {
+            let mut registry =
+                ProtocolRegistry::try_deserialize(&mut &registry_info.try_borrow_data()?[..])?;
+            registry.version = PROTOCOL_REGISTRY_VERSION;
+            registry
+        };
+        require_keys_eq!(
+            registry.vault,
+            vault_key,
+            VaultError::InvalidMigrationAccounts
+        );
+
+        // Legacy registries shrink; the freed rent goes to the authority
+        resize_account(
+            &registry_info,
+            &ctx.accounts.authority,
+            &ctx.accounts.system_program,
+            ProtocolRegistry::SPACE,
+        )?;
+        registry.try_serialize(&mut &mut registry_info.try_borrow_mut_data()?[..])?;
+    } else {
+        require!(
+            ctx.remaining_accounts.is_empty(),
+            VaultError::InvalidMigrationAccounts
+        );
+    }
+
+    // Emit event
+    emit!(VaultMigrated {
+        vault: vault_key,
+        from_version,
+        to_version: VAULT_STATE_VERSION,
+        registry_migrated,
+        protocols_created,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Create one `ApprovedProtocol` PDA per inline entry of a legacy registry
+fn create_legacy_protocols<'info>(
+    ctx: &Context<'_, '_, 'info, 'info, MigrateVault<'info>>,
+    legacy: &LegacyProtocolRegistry,
+) -> Result<u32> {
+    let vault_key = ctx.accounts.vault_state.key();
+    require!(
+        ctx.remaining_accounts.len() == legacy.approved_protocols.len() * 2,
+        VaultError::InvalidMigrationAccounts
+    );
+
+    let rent_lamports = Rent::get()?.minimum_balance(ApprovedProtocol::SPACE);
+    for (entry, pair) in legacy
+        .approved_protocols
+        .iter()
+        .zip(ctx.remaining_accounts.chunks_exact(2))
+    {
+        let (expected_entry, bump) = Pubkey::find_program_address(
+            &[PROTOCOL_SEED, vault_key.as_ref(), entry.target.as_ref()],
+            ctx.program_id,
+        );
+        require_keys_eq!(
+            pair[0].key(),
+            expected_entry,
+            VaultError::InvalidMigrationAccounts
+        );
+        require_keys_eq!(
+            pair[1].key(),
+            entry.target,
+            VaultError::InvalidMigrationAccounts
+        );
+
+        // Security: try_from checks token program ownership and account layout.
+        // Targets closed since they were approved cannot be pinned to an owner,
+        // so they are migrated disabled
+        let expected_owner = InterfaceAccount::<'info, TokenAccount>::try_from(&pair[1])
+            .map(|target_token_account| target_token_account.owner)
+            .ok();
+
+        // Fails if the entry already exists
+        create_protocol_account(
+            ctx,
+            &pair[0],
+            &[
+                PROTOCOL_SEED,
+                vault_key.as_ref(),
+                entry.target.as_ref(),
+                &[bump],
+            ],
+            rent_lamports,
+        )?;
+
+        let mut protocol = ApprovedProtocol::from_legacy(
+            vault_key,
+            entry,
+            expected_owner.unwrap_or_default(),
+            *pair[1].owner,
+            bump,
+        );
+        protocol.enabled = protocol.enabled && expected_owner.is_some();
+        protocol.try_serialize(&mut &mut pair[0].try_borrow_mut_data()?[..])?;
+    }
+
+    u32::try_from(legacy.approved_protocols.len()).map_err(|_| error!(VaultError::MathOverflow))
+}
+
+/// Create a program-owned `ApprovedProtocol` PDA
+///
+/// Mirrors Anchor's `init`: a pre-funded PDA cannot block creation.
+fn create_protocol_account<'info>(
+    ctx: &Context<'_, '_, 'info, 'info, MigrateVault<'info>>,
+    protocol: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+    lamports: u64,
+) -> Result<()> {
+    let system_program = ctx.accounts.system_program.to_account_info();
+    let current_lamports = protocol.lamports();
+    if current_lamports == 0 {
+        return system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program,
+                CreateAccount {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: protocol.clone(),
+                },
+                &[seeds],
+            ),
+            lamports,
+            ApprovedProtocol::SPACE as u64,
+            ctx.program_id,
+        );
+    }
+
+    if current_lamports < lamports {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: protocol.clone(),
+                },
+            ),
+            lamports - current_lamports,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            Allocate {
+                account_to_allocate: protocol.clone(),
+            },
+            &[seeds],
+        ),
+        ApprovedProtocol::SPACE as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program,
+            Assign {
+                account_to_assign: protocol.clone(),
+            },
+            &[seeds],
+        ),
+        ctx.program_id,
+    )
+}
+
+/// Resize a program-owned account and keep it exactly rent exempt
+///
+/// Growth is paid by the authority; rent freed by shrinking is returned to it.
+fn resize_account<'info>(
+    info: &AccountInfo<'info>,
+    authority: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    new_len: usize,
+) -> Result<()> {
+    if info.data_len() == new_len {
+        return Ok(());
+    }
+
+    let required = Rent::get()?.minimum_balance(new_len);
+    let current = info.lamports();
+    if required > current {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: authority.to_account_info(),
+                    to: info.clone(),
+                },
+            ),
+            required - current,
+        )?;
+    } else {
+        let authority_info = authority.to_account_info();
+        **authority_info.try_borrow_mut_lamports()? = authority_info
+            .lamports()
+            .checked_add(current - required)
+            .ok_or(VaultError::MathOverflow)?;
+        **info.try_borrow_mut_lamports()? = required;
+    }
+
+    info.resize(new_len)?;
+    Ok(())
+}
@@ -1,12 +1,75 @@
+#![allow(ambiguous_glob_reexports)]
+
 pub mod add_protocol;
+pub mod cancel_redeem;
+pub mod claim_redeem;
+pub mod close_vault;
 pub mod deposit;
+pub mod deposit_sol;
+pub mod divest_strategy;
+pub mod fulfill_requests;
 pub mod initialize;
+pub mod initialize_config;
 pub mod invest;
+pub mod invest_strategy;
+pub mod max_deposit;
+pub mod max_invest;
+pub mod migrate_vault;
+pub mod rebalance;
+pub mod recall_funds;
+pub mod register_vault;
+pub mod remove_protocol;
+pub mod report_strategy;
+pub mod request_redeem;
+pub mod set_allowed_mint;
+pub mod set_deposit_cap;
+pub mod set_depositor_root;
+pub mod set_lockup_duration;
+pub mod set_min_idle;
+pub mod set_referral_fee;
+pub mod set_target_weight;
+pub mod set_user_deposit_cap;
+pub mod shutdown;
 pub mod toggle_protocol;
+pub mod unlock_shares;
+pub mod update_config;
+pub mod update_share_metadata;
+pub mod withdraw_sol;
+pub mod write_off_protocol;
 
 pub use add_protocol::*;
+pub use cancel_redeem::*;
+pub use claim_redeem::*;
+pub use close_vault::*;
 pub use deposit::*;
+pub use deposit_sol::*;
+pub use divest_strategy::*;
+pub use fulfill_requests::*;
 pub use initialize::*;
+pub use initialize_config::*;
 pub use invest::*;
+pub use invest_strategy::*;
+pub use max_deposit::*;
+pub use max_invest::*;
+pub use migrate_vault::*;
+pub use rebalance::*;
+pub use recall_funds::*;
+pub use register_vault::*;
+pub use remove_protocol::*;
+pub use report_strategy::*;
+pub use request_redeem::*;
+pub use set_allowed_mint::*;
+pub use set_deposit_cap::*;
+pub use set_depositor_root::*;
+pub use set_lockup_duration::*;
+pub use set_min_idle::*;
+pub use set_referral_fee::*;
+pub use set_target_weight::*;
+pub use set_user_deposit_cap::*;
+pub use shutdown::*;
 pub use toggle_protocol::*;
-
+pub use unlock_shares::*;
+pub use update_config::*;
+pub use update_share_metadata::*;
+pub use withdraw_sol::*;
+pub use write_off_protocol::*;
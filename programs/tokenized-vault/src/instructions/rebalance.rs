@@ -0,0 +1,235 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{constants::*, errors::*, events::*, state::*};
+
+/// Move vault assets between the vault token account and approved protocols
+/// until each protocol is within the tolerance band of its target weight
+///
+/// Remaining accounts: `(protocol, target_token_account)` pairs, both writable.
+/// Over-allocated targets not owned by `vault_authority` are skipped: the vault
+/// cannot sign for them, so their excess is reported with `RebalanceSkipped`.
+#[derive(Accounts)]
+pub struct Rebalance<'info> {
+    /// Vault authority - only they can move allocations
+    /// Security: Must be signer and match vault_state.authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Vault state PDA
+    /// Security: has_one constraint validates authority from state
+    #[account(
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.bump,
+        has_one = authority @ VaultError::Unauthorized,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// Asset mint
+    /// Security: Must match vault_state.asset_mint and token_program
+    #[account(
+        address = vault_state.asset_mint,
+        mint::token_program = token_program,
+    )]
+    pub asset_mint: InterfaceAccount<'info, Mint>,
+
+    /// Vault authority PDA
+    /// Security: CHECK constraint, validated by seeds
+    /// CHECK: PDA used as authority, validated by seeds
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.authority_bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Vault's token account (idle assets)
+    /// Security: Must be the vault_authority ATA, not another vault-owned account
+    #[account(
+        mut,
+        associated_token::mint = asset_mint,
+        associated_token::authority = vault_authority,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token program that owns the asset mint
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// One validated `(protocol, target_token_account)` pair
+struct Leg<'info> {
+    protocol: Account<'info, ApprovedProtocol>,
+    target_token_account: InterfaceAccount<'info, TokenAccount>,
+    gap: AllocationGap,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Rebalance<'info>>,
+    tolerance_bps: u16,
+) -> Result<()> {
+    // CHECKS: Validate tolerance and remaining accounts
+    require!(
+        tolerance_bps as u64 <= BPS_DENOMINATOR,
+        VaultError::InvalidTargetWeight
+    );
+    let pairs = ctx.remaining_accounts.chunks_exact(2);
+    require!(
+        pairs.remainder().is_empty(),
+        VaultError::InvalidRebalanceAccounts
+    );
+
+    let vault_state = &ctx.accounts.vault_state;
+    let vault_key = vault_state.key();
+    let vault_authority_key = ctx.accounts.vault_authority.key();
+
+    let mut legs = Vec::with_capacity(ctx.remaining_accounts.len() / 2);
+    for pair in pairs {
+        // Security: try_from checks program ownership and account layout
+        let protocol: Account<'info, ApprovedProtocol> = Account::try_from(&pair[0])?;
+        let target_token_account: InterfaceAccount<'info, TokenAccount> =
+            InterfaceAccount::try_from(&pair[1])?;
+
+        let (expected_entry, _) = Pubkey::find_program_address(
+            &[PROTOCOL_SEED, vault_key.as_ref(), protocol.target.as_ref()],
+            ctx.program_id,
+        );
+        require!(
+            protocol.key() == expected_entry && protocol.vault == vault_key,
+            VaultError::InvalidRebalanceAccounts
+        );
+        require!(
+            target_token_account.key() == protocol.target,
+            VaultError::InvalidRebalanceAccounts
+        );
+        require!(
+            !legs.iter().any(|leg: &Leg| leg.protocol.key() == protocol.key()),
+            VaultError::InvalidRebalanceAccounts
+        );
+        require!(
+            target_token_account.mint == vault_state.asset_mint,
+            VaultError::InvalidMint
+        );
+        require!(
+            target_token_account.owner == protocol.expected_owner,
+            VaultError::TargetOwnerMismatch
+        );
+
+        let gap = protocol.allocation_gap(vault_state.total_assets, tolerance_bps)?;
+        legs.push(Leg {
+            protocol,
+            target_token_account,
+            gap,
+        });
+    }
+
+    let asset_mint_key = vault_state.asset_mint;
+    let authority_bump = vault_state.authority_bump;
+    let id_seed = vault_id_seed(vault_state.vault_id);
+    let authority_seeds: &[&[u8]] = &[
+        VAULT_AUTHORITY_SEED,
+        asset_mint_key.as_ref(),
+        &id_seed,
+        &[authority_bump],
+    ];
+    let signer_seeds = &[authority_seeds];
+    let decimals = ctx.accounts.asset_mint.decimals;
+    let timestamp = Clock::get()?.unix_timestamp;
+
+    // Withdraw from over-allocated protocols first so the freed assets can fund
+    // under-allocated ones in the same instruction
+    for leg in legs.iter_mut() {
+        let AllocationGap::Over(amount) = leg.gap else {
+            continue;
+        };
+        if leg.target_token_account.owner != vault_authority_key {
+            emit!(RebalanceSkipped {
+                vault: vault_key,
+                target: leg.protocol.target,
+                excess: amount,
+                invested_amount: leg.protocol.invested_amount,
+                target_weight_bps: leg.protocol.target_weight_bps,
+                timestamp,
+            });
+            continue;
+        }
+
+        // EFFECTS: Track divestment before the transfer
+        leg.protocol.track_divestment(amount)?;
+
+        // INTERACTIONS: Transfer target -> vault, signed by vault_authority
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: leg.target_token_account.to_account_info(),
+                mint: ctx.accounts.asset_mint.to_account_info(),
+                to: ctx.accounts.vault_token_account.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token_interface::transfer_checked(transfer_ctx, amount, decimals)?;
+
+        emit!(Rebalanced {
+            vault: vault_key,
+            target: leg.protocol.target,
+            from: leg.target_token_account.key(),
+            to: ctx.accounts.vault_token_account.key(),
+            amount,
+            invested_amount: leg.protocol.invested_amount,
+            target_weight_bps: leg.protocol.target_weight_bps,
+            total_assets: vault_state.total_assets,
+            timestamp,
+        });
+    }
+
+    ctx.accounts.vault_token_account.reload()?;
+
+    for leg in legs.iter_mut() {
+        let AllocationGap::Under(amount) = leg.gap else {
+            continue;
+        };
+        vault_state.check_active()?;
+        require!(leg.protocol.enabled, VaultError::ProtocolNotApproved);
+        require!(
+            vault_state.idle_assets(ctx.accounts.vault_token_account.amount) >= amount,
+            VaultError::InsufficientVaultBalance
+        );
+        vault_state.check_idle_buffer(ctx.accounts.vault_token_account.amount, amount)?;
+
+        // EFFECTS: Track investment (enforces the protocol's cap)
+        leg.protocol.track_investment(amount)?;
+
+        // INTERACTIONS: Transfer vault -> target
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault_token_account.to_account_info(),
+                mint: ctx.accounts.asset_mint.to_account_info(),
+                to: leg.target_token_account.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token_interface::transfer_checked(transfer_ctx, amount, decimals)?;
+        ctx.accounts.vault_token_account.reload()?;
+
+        emit!(Rebalanced {
+            vault: vault_key,
+            target: leg.protocol.target,
+            from: ctx.accounts.vault_token_account.key(),
+            to: leg.target_token_account.key(),
+            amount,
+            invested_amount: leg.protocol.invested_amount,
+            target_weight_bps: leg.protocol.target_weight_bps,
+            total_assets: vault_state.total_assets,
+            timestamp,
+        });
+    }
+
+    // Persist updated protocol entries (not handled by Anchor for remaining accounts)
+    for leg in legs.iter() {
+        leg.protocol.exit(ctx.program_id)?;
+    }
+
+    Ok(())
+}
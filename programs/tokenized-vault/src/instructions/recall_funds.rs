@@ -0,0 +1,143 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{constants::*, errors::*, events::*, state::*};
+
+/// Pull invested assets back into a shut-down vault
+///
+/// Permissionless. Remaining accounts: `(protocol, target_token_account)` pairs,
+/// both writable. Only targets owned by `vault_authority` can be recalled;
+/// strategy adapters are wound down by the authority with `divest_strategy`.
+#[derive(Accounts)]
+pub struct RecallFunds<'info> {
+    /// Anyone may trigger a recall once the vault is shut down
+    pub caller: Signer<'info>,
+
+    /// Vault state PDA
+    /// Security: Validated by seeds; must be shut down
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.bump,
+        constraint = vault_state.is_shutdown @ VaultError::VaultNotShutdown,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// Asset mint
+    /// Security: Must match vault_state.asset_mint and token_program
+    #[account(
+        address = vault_state.asset_mint,
+        mint::token_program = token_program,
+    )]
+    pub asset_mint: InterfaceAccount<'info, Mint>,
+
+    /// Vault authority PDA
+    /// Security: CHECK constraint, validated by seeds
+    /// CHECK: PDA used as authority, validated by seeds
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.authority_bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Vault's token account (destination)
+    /// Security: Must be the vault_authority ATA, not another vault-owned account
+    #[account(
+        mut,
+        associated_token::mint = asset_mint,
+        associated_token::authority = vault_authority,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token program that owns the asset mint
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, RecallFunds<'info>>) -> Result<()> {
+    // CHECKS: Validate remaining accounts
+    let pairs = ctx.remaining_accounts.chunks_exact(2);
+    require!(
+        pairs.remainder().is_empty() && !ctx.remaining_accounts.is_empty(),
+        VaultError::InvalidRebalanceAccounts
+    );
+
+    let vault_state = &mut ctx.accounts.vault_state;
+    let vault_key = vault_state.key();
+    let vault_authority_key = ctx.accounts.vault_authority.key();
+
+    let asset_mint_key = vault_state.asset_mint;
+    let authority_bump = vault_state.authority_bump;
+    let id_seed = vault_id_seed(vault_state.vault_id);
+    let authority_seeds: &[&[u8]] = &[
+        VAULT_AUTHORITY_SEED,
+        asset_mint_key.as_ref(),
+        &id_seed,
+        &[authority_bump],
+    ];
+    let signer_seeds = &[authority_seeds];
+    let decimals = ctx.accounts.asset_mint.decimals;
+    let timestamp = Clock::get()?.unix_timestamp;
+
+    for pair in pairs {
+        // Security: try_from checks program ownership and account layout
+        let mut protocol: Account<'info, ApprovedProtocol> = Account::try_from(&pair[0])?;
+        let target_token_account: InterfaceAccount<'info, TokenAccount> =
+            InterfaceAccount::try_from(&pair[1])?;
+
+        let (expected_entry, _) = Pubkey::find_program_address(
+            &[PROTOCOL_SEED, vault_key.as_ref(), protocol.target.as_ref()],
+            ctx.program_id,
+        );
+        require!(
+            protocol.key() == expected_entry && protocol.vault == vault_key,
+            VaultError::InvalidRebalanceAccounts
+        );
+        require!(
+            target_token_account.key() == protocol.target,
+            VaultError::InvalidRebalanceAccounts
+        );
+        require!(
+            target_token_account.owner == vault_authority_key,
+            VaultError::TargetNotWithdrawable
+        );
+
+        // Recall the target's whole balance; yield above what the vault
+        // accounts for is a gain and a shortfall is written off as a loss
+        let amount = target_token_account.amount;
+        if amount == 0 && protocol.invested_amount == 0 {
+            continue;
+        }
+
+        // EFFECTS: Clear the tracked investment and book the difference before the transfer
+        let (gain, loss) = protocol.record_recall(amount);
+        vault_state.apply_report(gain, loss)?;
+        protocol.exit(ctx.program_id)?;
+
+        // INTERACTIONS: Transfer target -> vault, signed by vault_authority
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: target_token_account.to_account_info(),
+                mint: ctx.accounts.asset_mint.to_account_info(),
+                to: ctx.accounts.vault_token_account.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token_interface::transfer_checked(transfer_ctx, amount, decimals)?;
+
+        emit!(FundsRecalled {
+            vault: vault_key,
+            target: protocol.target,
+            amount,
+            invested_amount: protocol.invested_amount,
+            gain,
+            loss,
+            caller: ctx.accounts.caller.key(),
+            timestamp,
+        });
+    }
+
+    Ok(())
+}
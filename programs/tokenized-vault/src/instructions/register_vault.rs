@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::*, errors::*, events::*, state::*};
+
+/// Add a vault created before the program config to the vault index
+#[derive(Accounts)]
+pub struct RegisterVault<'info> {
+    /// Vault authority - pays for the index entry
+    /// Security: Must be signer and match vault_state.authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Vault state PDA
+    /// Security: has_one constraint validates authority; `indexed` prevents
+    /// registering a vault twice
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.bump,
+        has_one = authority @ VaultError::Unauthorized,
+        constraint = !vault_state.indexed @ VaultError::VaultAlreadyIndexed,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// Program config PDA
+    /// Security: Source of the next vault index
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    /// Vault index entry PDA for this vault
+    /// Security: Seeded by the config's vault count, so entries stay contiguous
+    #[account(
+        init,
+        payer = authority,
+        space = VaultIndexEntry::SPACE,
+        seeds = [VAULT_INDEX_SEED, &config.vault_count.to_le_bytes()],
+        bump
+    )]
+    pub vault_index_entry: Account<'info, VaultIndexEntry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<RegisterVault>) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+
+    // EFFECTS: Append the vault to the index and mark it as indexed
+    let index = ctx.accounts.config.next_vault_index()?;
+    let vault_state = &mut ctx.accounts.vault_state;
+    vault_state.indexed = true;
+
+    let vault_index_entry = &mut ctx.accounts.vault_index_entry;
+    vault_index_entry.index = index;
+    vault_index_entry.vault = vault_state.key();
+    vault_index_entry.asset_mint = vault_state.asset_mint;
+    vault_index_entry.vault_id = vault_state.vault_id;
+    vault_index_entry.authority = vault_state.authority;
+    vault_index_entry.created_at = timestamp;
+    vault_index_entry.bump = ctx.bumps.vault_index_entry;
+
+    // Emit event
+    emit!(VaultRegistered {
+        vault: vault_state.key(),
+        asset_mint: vault_state.asset_mint,
+        vault_id: vault_state.vault_id,
+        index,
+        timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::*, errors::*, events::*, state::*};
+
+/// Remove a protocol from the whitelist
+#[derive(Accounts)]
+#[instruction(target: Pubkey)]
+pub struct RemoveProtocol<'info> {
+    /// Vault authority - only they can manage protocols
+    /// Security: Receives the rent of the closed protocol entry
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Vault state PDA
+    #[account(
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.bump,
+        has_one = authority @ VaultError::Unauthorized,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// Protocol registry PDA
+    #[account(
+        mut,
+        seeds = [b"protocol_registry", vault_state.key().as_ref()],
+        bump = protocol_registry.bump,
+    )]
+    pub protocol_registry: Account<'info, ProtocolRegistry>,
+
+    /// Approved protocol entry PDA
+    /// Security: Closed only when nothing is invested in it
+    #[account(
+        mut,
+        close = authority,
+        seeds = [PROTOCOL_SEED, vault_state.key().as_ref(), target.as_ref()],
+        bump = protocol.bump,
+        constraint = protocol.invested_amount == 0 @ VaultError::ProtocolHasInvestments,
+    )]
+    pub protocol: Account<'info, ApprovedProtocol>,
+}
+
+pub fn handler(ctx: Context<RemoveProtocol>, target: Pubkey) -> Result<()> {
+    let registry = &mut ctx.accounts.protocol_registry;
+
+    // EFFECTS: Drop the entry from the registry count and weights (account closed by Anchor)
+    registry.protocol_count = registry
+        .protocol_count
+        .checked_sub(1)
+        .ok_or(VaultError::MathOverflow)?;
+    registry.update_total_weight(ctx.accounts.protocol.target_weight_bps, 0)?;
+
+    // Emit event
+    emit!(ProtocolRemoved {
+        vault: registry.vault,
+        target,
+        name: ctx.accounts.protocol.name.clone(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
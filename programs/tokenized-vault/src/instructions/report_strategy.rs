@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{adapter::AdapterAccounts, constants::*, errors::*, events::*, state::*};
+
+/// Book a strategy adapter's gain or loss into the vault
+///
+/// Remaining accounts: adapter-specific accounts, passed through unchanged.
+#[derive(Accounts)]
+pub struct ReportStrategy<'info> {
+    /// Vault authority - only they can move the share price
+    /// Security: Must be signer and match vault_state.authority
+    pub authority: Signer<'info>,
+
+    /// Vault state PDA
+    /// Security: has_one constraint validates authority from state
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.bump,
+        has_one = authority @ VaultError::Unauthorized,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// Approved protocol entry PDA for the adapter program
+    /// Security: Seeds bind the entry to this vault and adapter_program
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED, vault_state.key().as_ref(), adapter_program.key().as_ref()],
+        bump = protocol.bump,
+        constraint = protocol.metadata.kind == ProtocolKind::Adapter @ VaultError::InvalidAdapter,
+    )]
+    pub protocol: Account<'info, ApprovedProtocol>,
+
+    /// Strategy adapter program
+    /// CHECK: Whitelisted through the protocol entry seeds
+    #[account(executable)]
+    pub adapter_program: UncheckedAccount<'info>,
+
+    /// Asset mint
+    /// Security: Must match vault_state.asset_mint and token_program
+    #[account(
+        address = vault_state.asset_mint,
+        mint::token_program = token_program,
+    )]
+    pub asset_mint: InterfaceAccount<'info, Mint>,
+
+    /// Strategy authority PDA, passed to the adapter without signing
+    /// Security: CHECK constraint, validated by seeds
+    /// CHECK: PDA identifying the strategy's position, validated by seeds
+    #[account(
+        seeds = [STRATEGY_SEED, vault_state.key().as_ref(), adapter_program.key().as_ref()],
+        bump,
+    )]
+    pub strategy_authority: UncheckedAccount<'info>,
+
+    /// Strategy token account
+    /// Security: Must be the strategy_authority ATA
+    #[account(
+        associated_token::mint = asset_mint,
+        associated_token::authority = strategy_authority,
+        associated_token::token_program = token_program,
+    )]
+    pub strategy_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token program that owns the asset mint
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, ReportStrategy<'info>>) -> Result<()> {
+    // INTERACTIONS (read-only): Unsigned CPI, the adapter cannot move strategy funds
+    let adapter = AdapterAccounts {
+        adapter_program: ctx.accounts.adapter_program.to_account_info(),
+        strategy_authority: ctx.accounts.strategy_authority.to_account_info(),
+        strategy_token_account: ctx.accounts.strategy_token_account.to_account_info(),
+        asset_mint: ctx.accounts.asset_mint.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        remaining_accounts: ctx.remaining_accounts,
+    };
+    let value = adapter.total_value()?;
+
+    // EFFECTS: Mark the entry to the reported value and book the difference
+    let protocol = &mut ctx.accounts.protocol;
+    let previous_value = protocol.invested_amount;
+    let (gain, loss) = protocol.record_report(value);
+
+    let vault_state = &mut ctx.accounts.vault_state;
+    vault_state.apply_report(gain, loss)?;
+
+    // Emit event
+    emit!(StrategyReported {
+        vault: vault_state.key(),
+        target: protocol.target,
+        previous_value,
+        value,
+        gain,
+        loss,
+        total_assets: vault_state.total_assets,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{constants::*, errors::*, events::*, state::*};
+
+/// Escrow shares for asynchronous redemption (ERC-7540 requestRedeem)
+#[derive(Accounts)]
+pub struct RequestRedeem<'info> {
+    /// User requesting the redemption
+    /// Security: Must be signer, escrows their own shares
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// Vault state PDA
+    /// Security: Validated by seeds, tracks pending redeem shares
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.bump,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// Share mint
+    /// Security: Must match vault_state.share_mint and share_token_program
+    #[account(
+        address = vault_state.share_mint,
+        mint::token_program = share_token_program,
+    )]
+    pub share_mint: InterfaceAccount<'info, Mint>,
+
+    /// Vault authority PDA
+    /// Security: CHECK constraint, validated by seeds
+    /// CHECK: PDA used as escrow authority, validated by seeds
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.authority_bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// User's share token account (source)
+    /// Security: Must be owned by user and correct mint
+    #[account(
+        mut,
+        constraint = user_share_account.mint == vault_state.share_mint @ VaultError::InvalidMint,
+        constraint = user_share_account.owner == user.key() @ VaultError::InvalidOwner,
+    )]
+    pub user_share_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Vault-owned escrow for shares of pending requests, created on first use
+    /// Security: PDA token account whose authority is vault_authority
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [SHARE_ESCROW_SEED, vault_state.key().as_ref()],
+        bump,
+        token::mint = share_mint,
+        token::authority = vault_authority,
+        token::token_program = share_token_program,
+    )]
+    pub share_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    /// Redeem request PDA, one open request per user and vault
+    /// Security: init fails while a previous request is still open
+    #[account(
+        init,
+        payer = user,
+        space = RedeemRequest::SPACE,
+        seeds = [REDEEM_REQUEST_SEED, vault_state.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub redeem_request: Account<'info, RedeemRequest>,
+
+    /// Token program that owns the share mint
+    pub share_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<RequestRedeem>, shares: u64) -> Result<()> {
+    // CHECKS: Validate amount (locked shares are in the share escrow, not
+    // in user_share_account, so they cannot be requested)
+    require!(shares > 0, VaultError::ZeroWithdrawAmount);
+    let now = Clock::get()?.unix_timestamp;
+
+    // EFFECTS: Record the request; shares stay in total_shares until fulfilled
+    let vault_state = &mut ctx.accounts.vault_state;
+    vault_state.pending_redeem_shares = vault_state
+        .pending_redeem_shares
+        .checked_add(shares)
+        .ok_or(VaultError::MathOverflow)?;
+
+    let redeem_request = &mut ctx.accounts.redeem_request;
+    redeem_request.vault = vault_state.key();
+    redeem_request.user = ctx.accounts.user.key();
+    redeem_request.shares = shares;
+    redeem_request.assets = 0;
+    redeem_request.requested_at = now;
+    redeem_request.fulfilled_at = 0;
+    redeem_request.bump = ctx.bumps.redeem_request;
+
+    // INTERACTIONS: Move the shares into the vault-owned escrow
+    let transfer_ctx = CpiContext::new(
+        ctx.accounts.share_token_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.user_share_account.to_account_info(),
+            mint: ctx.accounts.share_mint.to_account_info(),
+            to: ctx.accounts.share_escrow.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        },
+    );
+    token_interface::transfer_checked(transfer_ctx, shares, ctx.accounts.share_mint.decimals)?;
+
+    // Emit event
+    emit!(RedeemRequested {
+        vault: vault_state.key(),
+        user: ctx.accounts.user.key(),
+        shares,
+        pending_redeem_shares: vault_state.pending_redeem_shares,
+        timestamp: now,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{constants::*, errors::*, events::*, state::*};
+
+/// Allow or disallow vault creation for an asset mint
+#[derive(Accounts)]
+pub struct SetAllowedMint<'info> {
+    /// Config admin
+    /// Security: Pays for the entry on first use
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Config PDA
+    /// Security: has_one constraint validates admin from state
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ VaultError::Unauthorized,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    /// Asset mint to allow or disallow
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Allowlist entry PDA for the mint
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = AllowedMint::SPACE,
+        seeds = [ALLOWED_MINT_SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub allowed_mint: Account<'info, AllowedMint>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SetAllowedMint>, allowed: bool) -> Result<()> {
+    let allowed_mint = &mut ctx.accounts.allowed_mint;
+
+    // EFFECTS: Update entry (disallowing does not affect existing vaults)
+    allowed_mint.mint = ctx.accounts.mint.key();
+    allowed_mint.allowed = allowed;
+    allowed_mint.bump = ctx.bumps.allowed_mint;
+
+    // Emit event
+    emit!(AssetMintAllowed {
+        mint: allowed_mint.mint,
+        allowed,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::*, errors::*, events::*, state::*};
+
+/// Set the vault-wide deposit cap
+#[derive(Accounts)]
+pub struct SetDepositCap<'info> {
+    /// Vault authority - only they can change the cap
+    pub authority: Signer<'info>,
+
+    /// Vault state PDA
+    /// Security: has_one constraint validates authority from state
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.bump,
+        has_one = authority @ VaultError::Unauthorized,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+pub fn handler(ctx: Context<SetDepositCap>, deposit_cap: u64) -> Result<()> {
+    let vault_state = &mut ctx.accounts.vault_state;
+
+    // EFFECTS: A cap below total_assets only blocks new deposits
+    vault_state.deposit_cap = deposit_cap;
+
+    // Emit event
+    emit!(DepositCapSet {
+        vault: vault_state.key(),
+        deposit_cap,
+        total_assets: vault_state.total_assets,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::*, errors::*, events::*, state::*};
+
+/// Rotate the Merkle root of allowed depositors
+#[derive(Accounts)]
+pub struct SetDepositorRoot<'info> {
+    /// Vault authority - only they can change the allowlist
+    pub authority: Signer<'info>,
+
+    /// Vault state PDA
+    /// Security: has_one constraint validates authority from state
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.bump,
+        has_one = authority @ VaultError::Unauthorized,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+pub fn handler(ctx: Context<SetDepositorRoot>, depositor_merkle_root: [u8; 32]) -> Result<()> {
+    let vault_state = &mut ctx.accounts.vault_state;
+
+    // EFFECTS: All zeros opens the vault to everyone; existing shares are unaffected
+    vault_state.depositor_merkle_root = depositor_merkle_root;
+
+    // Emit event
+    emit!(DepositorRootSet {
+        vault: vault_state.key(),
+        depositor_merkle_root,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::*, errors::*, events::*, state::*};
+
+/// Set how long new deposits stay locked
+#[derive(Accounts)]
+pub struct SetLockupDuration<'info> {
+    /// Vault authority - only they can change the lockup
+    pub authority: Signer<'info>,
+
+    /// Vault state PDA
+    /// Security: has_one constraint validates authority from state
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.bump,
+        has_one = authority @ VaultError::Unauthorized,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+pub fn handler(ctx: Context<SetLockupDuration>, lockup_duration: i64) -> Result<()> {
+    // CHECKS: Bound the lockup so deposits can't be trapped indefinitely
+    require!(
+        (0..=MAX_LOCKUP_DURATION).contains(&lockup_duration),
+        VaultError::InvalidLockupDuration
+    );
+
+    let vault_state = &mut ctx.accounts.vault_state;
+
+    // EFFECTS: Applies to future deposits; existing unlock times are kept
+    vault_state.lockup_duration = lockup_duration;
+
+    // Emit event
+    emit!(LockupDurationSet {
+        vault: vault_state.key(),
+        lockup_duration,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::*, errors::*, events::*, state::*};
+
+/// Set the share of total_assets that must stay idle
+#[derive(Accounts)]
+pub struct SetMinIdle<'info> {
+    /// Vault authority - only they can change the buffer
+    pub authority: Signer<'info>,
+
+    /// Vault state PDA
+    /// Security: has_one constraint validates authority from state
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.bump,
+        has_one = authority @ VaultError::Unauthorized,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+pub fn handler(ctx: Context<SetMinIdle>, min_idle_bps: u16) -> Result<()> {
+    // CHECKS: Validate the buffer is at most 100%
+    require!(
+        min_idle_bps as u64 <= BPS_DENOMINATOR,
+        VaultError::InvalidMinIdleBps
+    );
+
+    let vault_state = &mut ctx.accounts.vault_state;
+
+    // EFFECTS: Only constrains future investments; nothing is divested
+    vault_state.min_idle_bps = min_idle_bps;
+
+    // Emit event
+    emit!(MinIdleSet {
+        vault: vault_state.key(),
+        min_idle_bps,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::*, errors::*, events::*, state::*};
+
+/// Set the referral rate published for off-chain attribution
+#[derive(Accounts)]
+pub struct SetReferralFee<'info> {
+    /// Vault authority - only they can change the referral rate
+    pub authority: Signer<'info>,
+
+    /// Vault state PDA
+    /// Security: has_one constraint validates authority from state
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.bump,
+        has_one = authority @ VaultError::Unauthorized,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+pub fn handler(ctx: Context<SetReferralFee>, referral_fee_bps: u16) -> Result<()> {
+    // CHECKS: Validate the fee share is at most 100%
+    require!(
+        referral_fee_bps as u64 <= BPS_DENOMINATOR,
+        VaultError::FeeTooHigh
+    );
+
+    let vault_state = &mut ctx.accounts.vault_state;
+
+    // EFFECTS: Recorded only; the program never charges or pays this rate
+    vault_state.referral_fee_bps = referral_fee_bps;
+
+    // Emit event
+    emit!(ReferralFeeSet {
+        vault: vault_state.key(),
+        referral_fee_bps,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
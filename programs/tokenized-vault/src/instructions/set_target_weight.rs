@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::*, errors::*, events::*, state::*};
+
+/// Set a protocol's target allocation weight
+#[derive(Accounts)]
+#[instruction(target: Pubkey)]
+pub struct SetTargetWeight<'info> {
+    /// Vault authority - only they can manage allocations
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Vault state PDA
+    #[account(
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.bump,
+        has_one = authority @ VaultError::Unauthorized,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// Protocol registry PDA
+    /// Security: Tracks the weight total across all entries
+    #[account(
+        mut,
+        seeds = [b"protocol_registry", vault_state.key().as_ref()],
+        bump = protocol_registry.bump,
+    )]
+    pub protocol_registry: Account<'info, ProtocolRegistry>,
+
+    /// Approved protocol entry PDA
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED, vault_state.key().as_ref(), target.as_ref()],
+        bump = protocol.bump,
+    )]
+    pub protocol: Account<'info, ApprovedProtocol>,
+}
+
+pub fn handler(
+    ctx: Context<SetTargetWeight>,
+    target: Pubkey,
+    target_weight_bps: u16,
+) -> Result<()> {
+    let registry = &mut ctx.accounts.protocol_registry;
+    let protocol = &mut ctx.accounts.protocol;
+
+    // CHECKS + EFFECTS: Weights across the vault must stay within 100%
+    registry.update_total_weight(protocol.target_weight_bps, target_weight_bps)?;
+    protocol.target_weight_bps = target_weight_bps;
+
+    // Emit event
+    emit!(TargetWeightSet {
+        vault: registry.vault,
+        target,
+        target_weight_bps,
+        total_target_weight_bps: registry.total_target_weight_bps,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::*, errors::*, events::*, state::*};
+
+/// Set the cap on each user's cumulative deposits
+#[derive(Accounts)]
+pub struct SetUserDepositCap<'info> {
+    /// Vault authority - only they can change the cap
+    pub authority: Signer<'info>,
+
+    /// Vault state PDA
+    /// Security: has_one constraint validates authority from state
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.bump,
+        has_one = authority @ VaultError::Unauthorized,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+pub fn handler(ctx: Context<SetUserDepositCap>, per_user_deposit_cap: u64) -> Result<()> {
+    let vault_state = &mut ctx.accounts.vault_state;
+
+    // EFFECTS: Users already above a lowered cap simply cannot deposit more
+    vault_state.per_user_deposit_cap = per_user_deposit_cap;
+
+    // Emit event
+    emit!(UserDepositCapSet {
+        vault: vault_state.key(),
+        per_user_deposit_cap,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::*, errors::*, events::*, state::*};
+
+/// Permanently wind the vault down
+#[derive(Accounts)]
+pub struct Shutdown<'info> {
+    /// Vault authority - only they can shut the vault down
+    pub authority: Signer<'info>,
+
+    /// Vault state PDA
+    /// Security: has_one constraint validates authority from state
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.bump,
+        has_one = authority @ VaultError::Unauthorized,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+pub fn handler(ctx: Context<Shutdown>, reason: u8) -> Result<()> {
+    // CHECKS: Shutdown is one-way and happens once
+    let vault_state = &mut ctx.accounts.vault_state;
+    vault_state.check_active()?;
+
+    // EFFECTS: There is no instruction that clears the flag
+    vault_state.is_shutdown = true;
+    vault_state.shutdown_reason = reason;
+
+    // Emit event
+    emit!(VaultShutdown {
+        vault: vault_state.key(),
+        reason,
+        total_assets: vault_state.total_assets,
+        total_shares: vault_state.total_shares,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -4,6 +4,7 @@ use crate::{constants::*, errors::*, events::*, state::*};
 
 /// Toggle a protocol on/off in the whitelist
 #[derive(Accounts)]
+#[instruction(target: Pubkey)]
 pub struct ToggleProtocol<'info> {
     /// Vault authority - only they can manage protocols
     #[account(mut)]
@@ -11,19 +12,19 @@ pub struct ToggleProtocol<'info> {
 
     /// Vault state PDA
     #[account(
-        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref()],
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
         bump = vault_state.bump,
         has_one = authority @ VaultError::Unauthorized,
     )]
     pub vault_state: Account<'info, VaultState>,
 
-    /// Protocol registry PDA
+    /// Approved protocol entry PDA
     #[account(
         mut,
-        seeds = [b"protocol_registry", vault_state.key().as_ref()],
-        bump = protocol_registry.bump,
+        seeds = [PROTOCOL_SEED, vault_state.key().as_ref(), target.as_ref()],
+        bump = protocol.bump,
     )]
-    pub protocol_registry: Account<'info, ProtocolRegistry>,
+    pub protocol: Account<'info, ApprovedProtocol>,
 }
 
 pub fn handler(
@@ -31,18 +32,13 @@ pub fn handler(
     target: Pubkey,
     enabled: bool,
 ) -> Result<()> {
-    let registry = &mut ctx.accounts.protocol_registry;
-
-    // Find and toggle protocol
-    let protocol = registry
-        .get_protocol_mut(&target)
-        .ok_or(VaultError::ProtocolNotFound)?;
+    let protocol = &mut ctx.accounts.protocol;
 
     protocol.enabled = enabled;
 
     // Emit event
     emit!(ProtocolToggled {
-        vault: registry.vault,
+        vault: protocol.vault,
         target,
         enabled,
         timestamp: Clock::get()?.unix_timestamp,
@@ -50,5 +46,3 @@ pub fn handler(
 
     Ok(())
 }
-
-
@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{constants::*, errors::*, events::*, state::*};
+
+/// Release a user's locked shares from the share escrow once the lockup ends
+#[derive(Accounts)]
+pub struct UnlockShares<'info> {
+    /// User whose deposit shares are locked
+    /// Security: Must be signer, only releases their own position
+    pub user: Signer<'info>,
+
+    /// Vault state PDA
+    /// Security: Validated by seeds; a shutdown waives the lockup
+    #[account(
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.bump,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// Share mint
+    /// Security: Must match vault_state.share_mint and share_token_program
+    #[account(
+        address = vault_state.share_mint,
+        mint::token_program = share_token_program,
+    )]
+    pub share_mint: InterfaceAccount<'info, Mint>,
+
+    /// Vault authority PDA
+    /// Security: CHECK constraint, validated by seeds
+    /// CHECK: PDA used as escrow authority, validated by seeds
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.authority_bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// User's share token account (destination)
+    /// Security: Must be owned by user and correct mint
+    #[account(
+        mut,
+        constraint = user_share_account.mint == vault_state.share_mint @ VaultError::InvalidMint,
+        constraint = user_share_account.owner == user.key() @ VaultError::InvalidOwner,
+    )]
+    pub user_share_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Vault-owned share escrow holding locked shares
+    /// Security: Validated by seeds
+    #[account(
+        mut,
+        seeds = [SHARE_ESCROW_SEED, vault_state.key().as_ref()],
+        bump,
+    )]
+    pub share_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    /// User's position PDA, tracks the locked shares and unlock time
+    /// Security: Seeds bind it to this vault and user
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, vault_state.key().as_ref(), user.key().as_ref()],
+        bump = user_position.bump,
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    /// Token program that owns the share mint
+    pub share_token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler(ctx: Context<UnlockShares>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let vault_state = &ctx.accounts.vault_state;
+
+    // CHECKS: Something to release, and the lockup is over (or waived)
+    let shares = ctx.accounts.user_position.locked_shares;
+    require!(shares > 0, VaultError::NoLockedShares);
+    vault_state.check_unlockable(&ctx.accounts.user_position, now)?;
+
+    // EFFECTS: Clear the locked balance before moving tokens
+    ctx.accounts.user_position.locked_shares = 0;
+
+    // INTERACTIONS: Release shares from escrow, signed by vault_authority
+    let asset_mint_key = vault_state.asset_mint;
+    let authority_bump = vault_state.authority_bump;
+    let id_seed = vault_id_seed(vault_state.vault_id);
+    let authority_seeds: &[&[u8]] = &[
+        VAULT_AUTHORITY_SEED,
+        asset_mint_key.as_ref(),
+        &id_seed,
+        &[authority_bump],
+    ];
+    let signer_seeds = &[authority_seeds];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.share_token_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.share_escrow.to_account_info(),
+            mint: ctx.accounts.share_mint.to_account_info(),
+            to: ctx.accounts.user_share_account.to_account_info(),
+            authority: ctx.accounts.vault_authority.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token_interface::transfer_checked(transfer_ctx, shares, ctx.accounts.share_mint.decimals)?;
+
+    // Emit event
+    emit!(SharesUnlocked {
+        vault: vault_state.key(),
+        user: ctx.accounts.user.key(),
+        shares,
+        timestamp: now,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::*, errors::*, events::*, state::*};
+
+/// Update the program-wide config
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    /// Config admin
+    pub admin: Signer<'info>,
+
+    /// Config PDA
+    /// Security: has_one constraint validates admin from state
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ VaultError::Unauthorized,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+}
+
+pub fn handler(ctx: Context<UpdateConfig>, new_admin: Pubkey, params: ConfigParams) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    // EFFECTS: Update config
+    config.apply(&params);
+    config.admin = new_admin;
+
+    // Emit event
+    emit!(ConfigUpdated {
+        admin: config.admin,
+        fee_recipient: config.fee_recipient,
+        creation_fee_lamports: config.creation_fee_lamports,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{self, Transfer},
+};
+use anchor_spl::{
+    token_2022::spl_token_2022::{
+        extension::{BaseStateWithExtensions, StateWithExtensions},
+        state::Mint as SplMint,
+    },
+    token_interface::{
+        self,
+        spl_token_metadata_interface::state::{Field, TokenMetadata},
+        Mint, Token2022, TokenMetadataUpdateField,
+    },
+};
+
+use crate::{constants::*, errors::*, events::*, state::*};
+
+/// Update the name, symbol and URI of the share mint's Token-2022 metadata
+#[derive(Accounts)]
+pub struct UpdateShareMetadata<'info> {
+    /// Vault authority - only they can update share metadata
+    /// Security: Pays any extra rent for longer metadata
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Vault state PDA
+    /// Security: has_one constraint validates authority from state
+    #[account(
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.bump,
+        has_one = authority @ VaultError::Unauthorized,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// Vault authority PDA - metadata update authority
+    /// CHECK: PDA used as signer, validated by seeds
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.authority_bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Share mint (holds the metadata extension)
+    /// Security: Must match vault_state.share_mint and be a Token-2022 mint
+    #[account(
+        mut,
+        address = vault_state.share_mint,
+        mint::token_program = share_token_program,
+    )]
+    pub share_mint: InterfaceAccount<'info, Mint>,
+
+    pub share_token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<UpdateShareMetadata>, share_metadata: ShareMetadata) -> Result<()> {
+    // CHECKS: Validate inputs and read the current metadata
+    share_metadata.validate()?;
+
+    let share_mint = ctx.accounts.share_mint.to_account_info();
+    let current = {
+        let data = share_mint.try_borrow_data()?;
+        let state = StateWithExtensions::<SplMint>::unpack(&data)?;
+        state
+            .get_variable_len_extension::<TokenMetadata>()
+            .map_err(|_| error!(VaultError::ShareMetadataNotFound))?
+    };
+
+    let mut updated = current.clone();
+    updated.name = share_metadata.name.clone();
+    updated.symbol = share_metadata.symbol.clone();
+    updated.uri = share_metadata.uri.clone();
+
+    // Token-2022 reallocs the mint for longer metadata; top up rent first
+    let new_len = share_mint
+        .data_len()
+        .checked_sub(current.tlv_size_of()?)
+        .and_then(|len| len.checked_add(updated.tlv_size_of().ok()?))
+        .ok_or(VaultError::MathOverflow)?;
+    let required_lamports = Rent::get()?.minimum_balance(new_len);
+    let current_lamports = share_mint.lamports();
+    if current_lamports < required_lamports {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: share_mint.clone(),
+                },
+            ),
+            required_lamports - current_lamports,
+        )?;
+    }
+
+    // INTERACTIONS: Update each field, signed by vault_authority
+    let asset_mint_key = ctx.accounts.vault_state.asset_mint;
+    let authority_bump = ctx.accounts.vault_state.authority_bump;
+    let id_seed = vault_id_seed(ctx.accounts.vault_state.vault_id);
+    let authority_seeds: &[&[u8]] = &[
+        VAULT_AUTHORITY_SEED,
+        asset_mint_key.as_ref(),
+        &id_seed,
+        &[authority_bump],
+    ];
+    let signer_seeds = &[authority_seeds];
+
+    for (field, value) in [
+        (Field::Name, share_metadata.name.clone()),
+        (Field::Symbol, share_metadata.symbol.clone()),
+        (Field::Uri, share_metadata.uri.clone()),
+    ] {
+        token_interface::token_metadata_update_field(
+            CpiContext::new_with_signer(
+                ctx.accounts.share_token_program.to_account_info(),
+                TokenMetadataUpdateField {
+                    program_id: ctx.accounts.share_token_program.to_account_info(),
+                    metadata: share_mint.clone(),
+                    update_authority: ctx.accounts.vault_authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            field,
+            value,
+        )?;
+    }
+
+    // Emit event
+    emit!(ShareMetadataUpdated {
+        vault: ctx.accounts.vault_state.key(),
+        share_mint: share_mint.key(),
+        name: share_metadata.name,
+        symbol: share_metadata.symbol,
+        uri: share_metadata.uri,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::spl_token,
+    token_2022::spl_token_2022,
+    token_interface::{
+        self, Burn, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+    },
+};
+
+use crate::{constants::*, errors::*, events::*, state::*};
+
+/// Redeem shares of a wSOL vault for native SOL
+#[derive(Accounts)]
+pub struct WithdrawSol<'info> {
+    /// User redeeming shares
+    /// Security: Must be signer, burns their own shares
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// Vault state PDA
+    /// Security: Validated by seeds, contains totals
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.bump,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// Asset mint
+    /// Security: Must match vault_state.asset_mint and be a native SOL mint
+    #[account(
+        address = vault_state.asset_mint,
+        mint::token_program = asset_token_program,
+        constraint = asset_mint.key() == spl_token::native_mint::ID
+            || asset_mint.key() == spl_token_2022::native_mint::ID
+            @ VaultError::NotNativeMint,
+    )]
+    pub asset_mint: InterfaceAccount<'info, Mint>,
+
+    /// Share mint
+    /// Security: Must match vault_state.share_mint and share_token_program
+    #[account(
+        mut,
+        address = vault_state.share_mint,
+        mint::token_program = share_token_program,
+    )]
+    pub share_mint: InterfaceAccount<'info, Mint>,
+
+    /// Vault authority PDA
+    /// Security: CHECK constraint, validated by seeds
+    /// CHECK: PDA used as authority, validated by seeds
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.authority_bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Temporary wSOL account the withdrawal is unwrapped through
+    /// Security: Fresh PDA owned by user, closed back to user in this instruction
+    #[account(
+        init,
+        payer = user,
+        seeds = [WSOL_TEMP_SEED, vault_state.key().as_ref(), user.key().as_ref()],
+        bump,
+        token::mint = asset_mint,
+        token::authority = user,
+        token::token_program = asset_token_program,
+    )]
+    pub user_wsol_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// User's share token account (source)
+    /// Security: Must be owned by user and correct mint
+    #[account(
+        mut,
+        constraint = user_share_account.mint == vault_state.share_mint @ VaultError::InvalidMint,
+        constraint = user_share_account.owner == user.key() @ VaultError::InvalidOwner,
+    )]
+    pub user_share_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Vault's token account
+    /// Security: Must be the vault_authority ATA, not another vault-owned account
+    #[account(
+        mut,
+        associated_token::mint = asset_mint,
+        associated_token::authority = vault_authority,
+        associated_token::token_program = asset_token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token program that owns the asset mint
+    pub asset_token_program: Interface<'info, TokenInterface>,
+
+    /// Token program that owns the share mint
+    pub share_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<WithdrawSol>, shares: u64) -> Result<()> {
+    // CHECKS: Validate amount and idle liquidity (locked shares are in the
+    // share escrow, not in user_share_account, so they cannot be burned here)
+    require!(shares > 0, VaultError::ZeroWithdrawAmount);
+
+    let vault_state = &mut ctx.accounts.vault_state;
+
+    // Calculate assets owed using ERC-4626 formula (rounds down, favoring the vault)
+    let assets = vault_state.calculate_assets(shares)?;
+    require!(assets > 0, VaultError::ZeroWithdrawAmount);
+    require!(
+        vault_state.idle_assets(ctx.accounts.vault_token_account.amount) >= assets,
+        VaultError::InsufficientLiquidity
+    );
+
+    // EFFECTS: Update vault state BEFORE moving tokens
+    vault_state.total_assets = vault_state
+        .total_assets
+        .checked_sub(assets)
+        .ok_or(VaultError::MathOverflow)?;
+
+    vault_state.total_shares = vault_state
+        .total_shares
+        .checked_sub(shares)
+        .ok_or(VaultError::MathOverflow)?;
+
+    // INTERACTIONS: Burn the user's shares
+    token_interface::burn(
+        CpiContext::new(
+            ctx.accounts.share_token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.share_mint.to_account_info(),
+                from: ctx.accounts.user_share_account.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        shares,
+    )?;
+
+    // Move the wSOL out of the vault into the temporary account
+    let asset_mint_key = vault_state.asset_mint;
+    let authority_bump = vault_state.authority_bump;
+    let id_seed = vault_id_seed(vault_state.vault_id);
+    let authority_seeds: &[&[u8]] = &[
+        VAULT_AUTHORITY_SEED,
+        asset_mint_key.as_ref(),
+        &id_seed,
+        &[authority_bump],
+    ];
+    let signer_seeds = &[authority_seeds];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.asset_token_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.vault_token_account.to_account_info(),
+            mint: ctx.accounts.asset_mint.to_account_info(),
+            to: ctx.accounts.user_wsol_account.to_account_info(),
+            authority: ctx.accounts.vault_authority.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token_interface::transfer_checked(transfer_ctx, assets, ctx.accounts.asset_mint.decimals)?;
+
+    // Closing the temporary account unwraps: its lamports (SOL + rent) go to the user
+    token_interface::close_account(CpiContext::new(
+        ctx.accounts.asset_token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.user_wsol_account.to_account_info(),
+            destination: ctx.accounts.user.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        },
+    ))?;
+
+    // Emit event
+    emit!(Withdrawn {
+        vault: vault_state.key(),
+        user: ctx.accounts.user.key(),
+        shares_burned: shares,
+        assets_withdrawn: assets,
+        total_assets: vault_state.total_assets,
+        total_shares: vault_state.total_shares,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
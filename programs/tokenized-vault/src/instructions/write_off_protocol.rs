@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::*, errors::*, events::*, state::*};
+
+/// Write off the tracked investment of a target the vault cannot withdraw from
+#[derive(Accounts)]
+#[instruction(target: Pubkey)]
+pub struct WriteOffProtocol<'info> {
+    /// Vault authority - only they can write off a protocol
+    pub authority: Signer<'info>,
+
+    /// Vault state PDA
+    /// Security: has_one validates authority; must be shut down so no one can
+    /// deposit at the written-down share price
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.bump,
+        has_one = authority @ VaultError::Unauthorized,
+        constraint = vault_state.is_shutdown @ VaultError::VaultNotShutdown,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// Vault authority PDA
+    /// Security: CHECK constraint, validated by seeds
+    /// CHECK: PDA only compared against the protocol's expected owner
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED, vault_state.asset_mint.as_ref(), &vault_id_seed(vault_state.vault_id)],
+        bump = vault_state.authority_bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Approved protocol entry PDA
+    /// Security: Only third-party-owned token account targets; vault-owned
+    /// targets go through recall_funds and adapters through report_strategy
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED, vault_state.key().as_ref(), target.as_ref()],
+        bump = protocol.bump,
+        constraint = protocol.metadata.kind != ProtocolKind::Adapter
+            && protocol.expected_owner != vault_authority.key()
+            @ VaultError::WriteOffNotAllowed,
+    )]
+    pub protocol: Account<'info, ApprovedProtocol>,
+}
+
+pub fn handler(ctx: Context<WriteOffProtocol>, target: Pubkey) -> Result<()> {
+    let protocol = &mut ctx.accounts.protocol;
+    let vault_state = &mut ctx.accounts.vault_state;
+
+    // CHECKS: Something to write off
+    let loss = protocol.invested_amount;
+    require!(loss > 0, VaultError::NothingToWriteOff);
+
+    // EFFECTS: Clear the entry and book the loss, so it can be removed and
+    // the vault closed
+    protocol.track_divestment(loss)?;
+    vault_state.apply_report(0, loss)?;
+
+    // Emit event
+    emit!(ProtocolWrittenOff {
+        vault: vault_state.key(),
+        target,
+        loss,
+        total_assets: vault_state.total_assets,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
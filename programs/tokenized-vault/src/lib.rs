@@ -4,6 +4,7 @@
 
 use anchor_lang::prelude::*;
 
+pub mod adapter;
 pub mod constants;
 pub mod errors;
 pub mod events;
@@ -11,6 +12,7 @@ pub mod instructions;
 pub mod state;
 
 use instructions::*;
+use state::{ConfigParams, ProtocolMetadata, ShareMetadata};
 
 declare_id!("VAULTvgMLuVNhWKYA2oYzH5gcz6XxsjXrqvnxTJbG8F");
 
@@ -18,14 +20,67 @@ declare_id!("VAULTvgMLuVNhWKYA2oYzH5gcz6XxsjXrqvnxTJbG8F");
 pub mod tokenized_vault {
     use super::*;
 
+    /// Create the program-wide config (admin, creation fee, vault index counter)
+    ///
+    /// Security considerations:
+    /// - Only the program upgrade authority can call, and only once
+    /// - Emits event for tracking
+    pub fn initialize_config(ctx: Context<InitializeConfig>, params: ConfigParams) -> Result<()> {
+        instructions::initialize_config::handler(ctx, params)
+    }
+
+    /// Update the program-wide config or hand it to a new admin
+    ///
+    /// Security considerations:
+    /// - Admin-only function (has_one constraint)
+    /// - Emits event for tracking
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        new_admin: Pubkey,
+        params: ConfigParams,
+    ) -> Result<()> {
+        instructions::update_config::handler(ctx, new_admin, params)
+    }
+
+    /// Allow or disallow vault creation for an asset mint
+    ///
+    /// Security considerations:
+    /// - Admin-only function (has_one constraint)
+    /// - Existing vaults for a disallowed mint keep working
+    /// - Emits event for tracking
+    pub fn set_allowed_mint(ctx: Context<SetAllowedMint>, allowed: bool) -> Result<()> {
+        instructions::set_allowed_mint::handler(ctx, allowed)
+    }
+
     /// Initialize a new vault for a given asset token
     ///
     /// Security considerations:
     /// - Validates authority is signer
+    /// - Asset mint must be allowed by the program config
+    /// - Charges the config's creation fee and appends the vault to the vault index
     /// - Initializes vault state with proper PDAs
     /// - Creates share mint with vault as mint authority
-    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
-        instructions::initialize::handler(ctx)
+    /// - Asset and share mints may each use SPL Token or Token-2022
+    /// - Optional share metadata (name/symbol/uri) stored in the Token-2022
+    ///   metadata extension, with vault_authority as update authority
+    /// - `vault_id` allows several vaults per asset mint; id 0 keeps the
+    ///   original one-per-mint PDA addresses
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        vault_id: u64,
+        share_metadata: Option<ShareMetadata>,
+    ) -> Result<()> {
+        instructions::initialize::handler(ctx, vault_id, share_metadata)
+    }
+
+    /// Add a vault created before the program config to the vault index
+    ///
+    /// Security considerations:
+    /// - Authority-only function (has_one constraint)
+    /// - Each vault can be indexed once (initialize indexes new vaults itself)
+    /// - Emits event for tracking
+    pub fn register_vault(ctx: Context<RegisterVault>) -> Result<()> {
+        instructions::register_vault::handler(ctx)
     }
 
     /// Deposit assets into the vault and receive shares
@@ -33,10 +88,270 @@ pub mod tokenized_vault {
     /// Security considerations:
     /// - Validates user token accounts (mint, owner)
     /// - Uses checked math for share calculation
-    /// - Follows checks-effects-interactions pattern
+    /// - Credits only assets actually received (Token-2022 transfer fees)
+    /// - Rejects deposits that would exceed the vault's deposit cap
+    /// - Tracks the user's cumulative deposits in a UserPosition PDA and
+    ///   enforces the per-user deposit cap
+    /// - Permissioned vaults require a Merkle proof that the user is allowed
+    ///   (pass an empty proof for open vaults)
+    /// - Optional referrer (not the depositor) is credited in a ReferralStats PDA
+    /// - Under a lockup, shares are minted to the vault's share escrow and
+    ///   released by `unlock_shares`, so they cannot be moved while locked
+    /// - Updates state before minting shares
+    /// - Emits event for tracking
+    pub fn deposit(
+        ctx: Context<Deposit>,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+        referrer: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::deposit::handler(ctx, amount, proof, referrer)
+    }
+
+    /// Deposit native SOL into a wSOL vault and receive shares
+    ///
+    /// Security considerations:
+    /// - Asset mint must be a native SOL mint
+    /// - Wraps lamports in a temporary wSOL PDA that is closed back to the user
+    /// - Same share math, allowlist, deposit caps, user position, referrer
+    ///   attribution and state updates as `deposit`
+    /// - Emits event for tracking
+    pub fn deposit_sol(
+        ctx: Context<DepositSol>,
+        lamports: u64,
+        proof: Vec<[u8; 32]>,
+        referrer: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::deposit_sol::handler(ctx, lamports, proof, referrer)
+    }
+
+    /// Set the vault-wide deposit cap on total_assets (0 = uncapped)
+    ///
+    /// Security considerations:
+    /// - Authority-only function (has_one constraint)
+    /// - Lowering the cap below total_assets only blocks new deposits
+    /// - Emits event for tracking
+    pub fn set_deposit_cap(ctx: Context<SetDepositCap>, deposit_cap: u64) -> Result<()> {
+        instructions::set_deposit_cap::handler(ctx, deposit_cap)
+    }
+
+    /// Set the cap on each user's cumulative deposits (0 = uncapped)
+    ///
+    /// Security considerations:
+    /// - Authority-only function (has_one constraint)
+    /// - Applies to cumulative deposits, so withdrawals do not free capacity
+    /// - Emits event for tracking
+    pub fn set_user_deposit_cap(
+        ctx: Context<SetUserDepositCap>,
+        per_user_deposit_cap: u64,
+    ) -> Result<()> {
+        instructions::set_user_deposit_cap::handler(ctx, per_user_deposit_cap)
+    }
+
+    /// Rotate the Merkle root of allowed depositors (all zeros = permissionless)
+    ///
+    /// Security considerations:
+    /// - Authority-only function (has_one constraint)
+    /// - Only gates new deposits; existing holders keep their shares
+    /// - Emits event for tracking
+    pub fn set_depositor_root(
+        ctx: Context<SetDepositorRoot>,
+        depositor_merkle_root: [u8; 32],
+    ) -> Result<()> {
+        instructions::set_depositor_root::handler(ctx, depositor_merkle_root)
+    }
+
+    /// Set how long each deposit's shares stay in escrow (0 = no lockup)
+    ///
+    /// Security considerations:
+    /// - Authority-only function (has_one constraint)
+    /// - Bounded to MAX_LOCKUP_DURATION (365 days)
+    /// - Only affects future deposits; existing unlock times are kept
+    /// - Emits event for tracking
+    pub fn set_lockup_duration(
+        ctx: Context<SetLockupDuration>,
+        lockup_duration: i64,
+    ) -> Result<()> {
+        instructions::set_lockup_duration::handler(ctx, lockup_duration)
+    }
+
+    /// Release a user's locked deposit shares from the share escrow
+    ///
+    /// Security considerations:
+    /// - Only the position's user can release (position PDA seeds)
+    /// - Rejected before the position's unlock time (waived after shutdown)
+    /// - Clears the locked balance before transferring
+    /// - Emits event for tracking
+    pub fn unlock_shares(ctx: Context<UnlockShares>) -> Result<()> {
+        instructions::unlock_shares::handler(ctx)
+    }
+
+    /// Remaining assets the vault accepts before its deposit cap (ERC-4626 maxDeposit)
+    ///
+    /// Read-only; returns u64::MAX when uncapped and 0 once shut down
+    pub fn max_deposit(ctx: Context<MaxDeposit>) -> Result<u64> {
+        instructions::max_deposit::handler(ctx)
+    }
+
+    /// Set the share of total_assets that `invest` must leave idle (in bps)
+    ///
+    /// Security considerations:
+    /// - Authority-only function (has_one constraint)
+    /// - Capped at 10000 bps
+    /// - Only constrains future invest / rebalance legs
+    /// - Emits event for tracking
+    pub fn set_min_idle(ctx: Context<SetMinIdle>, min_idle_bps: u16) -> Result<()> {
+        instructions::set_min_idle::handler(ctx, min_idle_bps)
+    }
+
+    /// Set the referral rate published for off-chain attribution (in bps)
+    ///
+    /// Security considerations:
+    /// - Authority-only function (has_one constraint)
+    /// - Capped at 10000 bps
+    /// - Metadata only: the program charges and pays no referral fees; any
+    ///   payout is computed off-chain from ReferralStats volume
+    /// - Emits event for tracking
+    pub fn set_referral_fee(ctx: Context<SetReferralFee>, referral_fee_bps: u16) -> Result<()> {
+        instructions::set_referral_fee::handler(ctx, referral_fee_bps)
+    }
+
+    /// Assets `invest` can move out without breaching the idle buffer
+    ///
+    /// Read-only; excludes assets reserved for redeem claims, 0 once shut down
+    pub fn max_invest(ctx: Context<MaxInvest>) -> Result<u64> {
+        instructions::max_invest::handler(ctx)
+    }
+
+    /// Redeem shares of a wSOL vault for native SOL
+    ///
+    /// Security considerations:
+    /// - Asset mint must be a native SOL mint
+    /// - Assets owed round down (favoring remaining holders)
+    /// - Only idle assets in vault_token_account can be withdrawn
+    /// - Locked shares sit in the share escrow, so only unlocked shares can be burned
+    /// - Updates state before burning shares and transferring
+    /// - Unwraps through a temporary wSOL PDA closed back to the user
+    /// - Emits event for tracking
+    pub fn withdraw_sol(ctx: Context<WithdrawSol>, shares: u64) -> Result<()> {
+        instructions::withdraw_sol::handler(ctx, shares)
+    }
+
+    /// Escrow shares for asynchronous redemption (ERC-7540 requestRedeem)
+    ///
+    /// Security considerations:
+    /// - Shares move into a vault-owned escrow; one open request per user
+    /// - Locked shares sit in the share escrow, so only unlocked shares can be requested
+    /// - Escrowed shares keep earning until the request is fulfilled
     /// - Emits event for tracking
-    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
-        instructions::deposit::handler(ctx, amount)
+    pub fn request_redeem(ctx: Context<RequestRedeem>, shares: u64) -> Result<()> {
+        instructions::request_redeem::handler(ctx, shares)
+    }
+
+    /// Fulfill a batch of redeem requests at a single exchange rate
+    ///
+    /// Security considerations:
+    /// - Authority-only while active; permissionless once the vault is shut down
+    /// - Requests passed as remaining accounts must be distinct, pending and for this vault
+    /// - Assets owed round down and must be covered by idle assets
+    /// - Reserved assets are excluded from total_assets, invest and rebalance
+    /// - Emits event for tracking
+    pub fn fulfill_requests<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FulfillRequests<'info>>,
+    ) -> Result<()> {
+        instructions::fulfill_requests::handler(ctx)
+    }
+
+    /// Collect the assets of a fulfilled redeem request
+    ///
+    /// Security considerations:
+    /// - Only the requesting user can claim (request PDA seeds)
+    /// - Pays exactly the assets locked in at fulfillment, then closes the request
+    /// - Emits event for tracking
+    pub fn claim_redeem(ctx: Context<ClaimRedeem>) -> Result<()> {
+        instructions::claim_redeem::handler(ctx)
+    }
+
+    /// Cancel a pending redeem request and return the escrowed shares
+    ///
+    /// Security considerations:
+    /// - Only the requesting user can cancel (request PDA seeds)
+    /// - Fails once the request has been fulfilled
+    /// - Emits event for tracking
+    pub fn cancel_redeem(ctx: Context<CancelRedeem>) -> Result<()> {
+        instructions::cancel_redeem::handler(ctx)
+    }
+
+    /// Permanently shut the vault down for wind-down (one-way)
+    ///
+    /// Security considerations:
+    /// - Authority-only function (has_one constraint)
+    /// - Blocks deposit, deposit_sol, invest and rebalance invest legs forever
+    /// - Withdrawals and redemptions stay open at the current price per share;
+    ///   locked shares can be released at once and anyone can fulfill redeem requests
+    /// - Emits event with the reason code
+    pub fn shutdown(ctx: Context<Shutdown>, reason: u8) -> Result<()> {
+        instructions::shutdown::handler(ctx, reason)
+    }
+
+    /// Recall invested assets from protocols into a shut-down vault
+    ///
+    /// Security considerations:
+    /// - Permissionless, only after shutdown
+    /// - Protocol entries and targets passed as remaining accounts are fully validated
+    /// - Only withdraws from token account targets owned by the vault authority
+    ///   PDA; adapters are divested by the authority instead
+    /// - Recalls the whole target balance and books the difference from the
+    ///   tracked invested amount as a gain or loss
+    /// - Emits one event per recalled protocol
+    pub fn recall_funds<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RecallFunds<'info>>,
+    ) -> Result<()> {
+        instructions::recall_funds::handler(ctx)
+    }
+
+    /// Close an emptied vault and reclaim the rent of its accounts
+    ///
+    /// Security considerations:
+    /// - Authority-only function (has_one constraint)
+    /// - Requires zero shares and redeem requests, and no remaining protocol
+    ///   entries (each was removed with nothing invested); leftover
+    ///   total_assets is rounding dust no share can claim
+    /// - Sweeps any leftover vault token balance to the authority, since
+    ///   nothing is owed to holders
+    /// - Closes vault_state, vault_token_account, protocol_registry and the
+    ///   share escrow; the share mint cannot be closed and stays with zero supply
+    /// - Emits event for tracking
+    pub fn close_vault(ctx: Context<CloseVault>) -> Result<()> {
+        instructions::close_vault::handler(ctx)
+    }
+
+    /// Write off a protocol target the vault cannot withdraw from
+    ///
+    /// Security considerations:
+    /// - Authority-only function (has_one constraint), only once shut down
+    /// - Only token account targets owned by a third party; vault-owned
+    ///   targets are recalled and adapters report their value instead
+    /// - Books the whole invested amount as a loss, so the entry can be
+    ///   removed and the vault closed
+    /// - Emits event for tracking
+    pub fn write_off_protocol(ctx: Context<WriteOffProtocol>, target: Pubkey) -> Result<()> {
+        instructions::write_off_protocol::handler(ctx, target)
+    }
+
+    /// Upgrade a vault and its protocol registry to the current account layouts
+    ///
+    /// Security considerations:
+    /// - Authority-only function (stored authority checked in the handler)
+    /// - Vault PDA re-derived from its stored seeds and bump
+    /// - Accounts are only resized to their exact current size and stay rent exempt
+    /// - Legacy registry entries must be passed in order; each becomes a new
+    ///   ApprovedProtocol PDA, so entries cannot be skipped or duplicated
+    /// - Emits event for tracking
+    pub fn migrate_vault<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MigrateVault<'info>>,
+    ) -> Result<()> {
+        instructions::migrate_vault::handler(ctx)
     }
 
     /// Invest vault assets into a whitelisted protocol via CPI
@@ -44,27 +359,87 @@ pub mod tokenized_vault {
     /// Architecture: Validates target against protocol registry whitelist
     /// Security considerations:
     /// - Authority-only function (has_one constraint)
-    /// - Validates target against approved protocol registry
+    /// - Validates target against its approved protocol entry
+    /// - Validates target token account is still owned by the approved owner
     /// - Tracks invested amount per protocol
+    /// - Keeps idle assets at or above min_idle_bps of total_assets
     /// - Prevents rug pulls by restricting investment destinations
     /// - Emits event for transparency
     pub fn invest(ctx: Context<Invest>, amount: u64) -> Result<()> {
         instructions::invest::handler(ctx, amount)
     }
 
+    /// Invest vault assets into a whitelisted strategy adapter via CPI
+    ///
+    /// Security considerations:
+    /// - Authority-only function (has_one constraint)
+    /// - Adapter must be whitelisted as an enabled `Adapter` protocol entry
+    /// - Same balance, cap and idle buffer checks as `invest`
+    /// - Moves `amount` to the adapter's strategy token account first; the
+    ///   adapter only gets the strategy authority PDA, never vault_authority
+    /// - The adapter must take exactly the amount requested and reported
+    /// - Emits event for tracking
+    pub fn invest_strategy<'info>(
+        ctx: Context<'_, '_, 'info, 'info, InvestStrategy<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::invest_strategy::handler(ctx, amount)
+    }
+
+    /// Withdraw vault assets from a whitelisted strategy adapter via CPI
+    ///
+    /// Security considerations:
+    /// - Authority-only function (has_one constraint), also after shutdown,
+    ///   since the caller chooses the adapter accounts
+    /// - Bounded by the protocol's invested_amount (report gains first)
+    /// - Signed by the strategy authority PDA only; exactly the amount reported
+    ///   must arrive in the strategy token account, and is swept to the vault
+    /// - Emits event for tracking
+    pub fn divest_strategy<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DivestStrategy<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::divest_strategy::handler(ctx, amount)
+    }
+
+    /// Book a strategy adapter's gain or loss into total_assets
+    ///
+    /// Security considerations:
+    /// - Authority-only function (has_one constraint), since it moves the share price
+    /// - Read-only CPI without any signature
+    /// - Checked math on total_assets
+    /// - Emits event for tracking
+    pub fn report_strategy<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReportStrategy<'info>>,
+    ) -> Result<()> {
+        instructions::report_strategy::handler(ctx)
+    }
+
     /// Add a new protocol to the approved whitelist
     ///
     /// Security considerations:
     /// - Authority-only function
-    /// - Validates protocol doesn't already exist
-    /// - Enforces registry size limits
+    /// - Creates one PDA per (vault, target), so duplicates cannot exist
+    /// - Pins the owner the target token account must keep
+    /// - Optional per-protocol invest cap
+    /// - Stores protocol kind, owner program, risk tier and metadata URI
     /// - Emits event for tracking
     pub fn add_protocol(
         ctx: Context<AddProtocol>,
         target: Pubkey,
+        expected_owner: Pubkey,
         name: String,
+        invest_cap: Option<u64>,
+        metadata: ProtocolMetadata,
     ) -> Result<()> {
-        instructions::add_protocol::handler(ctx, target, name)
+        instructions::add_protocol::handler(
+            ctx,
+            target,
+            expected_owner,
+            name,
+            invest_cap,
+            metadata,
+        )
     }
 
     /// Toggle a protocol on/off in the whitelist
@@ -81,5 +456,58 @@ pub mod tokenized_vault {
     ) -> Result<()> {
         instructions::toggle_protocol::handler(ctx, target, enabled)
     }
-}
 
+    /// Remove a protocol from the whitelist and close its entry
+    ///
+    /// Security considerations:
+    /// - Authority-only function
+    /// - Only protocols with zero invested amount can be removed
+    /// - Returns the entry's rent to the authority
+    /// - Emits event for tracking
+    pub fn remove_protocol(ctx: Context<RemoveProtocol>, target: Pubkey) -> Result<()> {
+        instructions::remove_protocol::handler(ctx, target)
+    }
+
+    /// Set a protocol's target allocation weight in basis points
+    ///
+    /// Security considerations:
+    /// - Authority-only function
+    /// - Weights across all protocols cannot exceed 100%
+    /// - Emits event for tracking
+    pub fn set_target_weight(
+        ctx: Context<SetTargetWeight>,
+        target: Pubkey,
+        target_weight_bps: u16,
+    ) -> Result<()> {
+        instructions::set_target_weight::handler(ctx, target, target_weight_bps)
+    }
+
+    /// Move assets between the vault and approved protocols toward target weights
+    ///
+    /// Security considerations:
+    /// - Authority-only function
+    /// - Protocol entries and targets passed as remaining accounts are fully validated
+    /// - Only withdraws from targets owned by the vault authority PDA
+    /// - Invest legs require enabled protocols and respect invest caps
+    /// - Emits one event per transfer
+    pub fn rebalance<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Rebalance<'info>>,
+        tolerance_bps: u16,
+    ) -> Result<()> {
+        instructions::rebalance::handler(ctx, tolerance_bps)
+    }
+
+    /// Update the share mint's name, symbol and URI
+    ///
+    /// Security considerations:
+    /// - Authority-only function
+    /// - Signed by vault_authority PDA (metadata update authority)
+    /// - Authority pays any extra rent for longer metadata
+    /// - Emits event for tracking
+    pub fn update_share_metadata(
+        ctx: Context<UpdateShareMetadata>,
+        share_metadata: ShareMetadata,
+    ) -> Result<()> {
+        instructions::update_share_metadata::handler(ctx, share_metadata)
+    }
+}
@@ -1,4 +1,11 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    spl_pod::optional_keys::OptionalNonZeroPubkey,
+    spl_token_metadata_interface::state::TokenMetadata,
+};
+// Anchor 0.32 no longer re-exports `solana_program::hash`; anchor-spl's
+// associated token crate still carries the full `solana_program`
+use anchor_spl::associated_token::spl_associated_token_account::solana_program::hash::hashv;
 
 /// Global vault state tracking assets and shares
 ///
@@ -6,7 +13,8 @@ use anchor_lang::prelude::*;
 /// - Authority stored in state (not instruction args)
 /// - Total assets and shares tracked for ERC-4626 math
 /// - Bumps stored for efficient PDA signing
-/// - 128 bytes padding for future upgrades
+/// - Padding for future upgrades (vault_id was carved out of it)
+/// - `version` records the layout, so `migrate_vault` can upgrade older accounts
 #[account]
 pub struct VaultState {
     /// Authority that can invest vault assets and manage protocol registry
@@ -33,80 +41,917 @@ pub struct VaultState {
     /// Bump seed for vault authority PDA
     pub authority_bump: u8,         // 1 byte
     
+    /// Distinguishes vaults of the same asset mint (0 = original vault)
+    pub vault_id: u64,              // 8 bytes (taken from padding)
+    
+    /// Maximum total_assets accepted by deposits (0 = uncapped)
+    pub deposit_cap: u64,           // 8 bytes (taken from padding)
+    
+    /// Maximum cumulative deposits per user (0 = uncapped)
+    pub per_user_deposit_cap: u64,  // 8 bytes (taken from padding)
+    
+    /// Merkle root of allowed depositors (all zeros = permissionless)
+    pub depositor_merkle_root: [u8; 32], // 32 bytes (taken from padding)
+    
+    /// Seconds each deposit stays locked before its shares can be redeemed (0 = none)
+    pub lockup_duration: i64,       // 8 bytes (taken from padding)
+    
+    /// Shares escrowed by open redeem requests (still counted in total_shares)
+    pub pending_redeem_shares: u64, // 8 bytes (taken from padding)
+    
+    /// Assets of fulfilled redeem requests awaiting claim (excluded from total_assets)
+    pub claimable_assets: u64,      // 8 bytes (taken from padding)
+    
+    /// Share of total_assets that `invest` must leave idle, in bps (0 = none)
+    pub min_idle_bps: u16,          // 2 bytes (taken from padding)
+    
+    /// Referral rate in bps, metadata for off-chain attribution only; the
+    /// program never charges or pays it
+    pub referral_fee_bps: u16,      // 2 bytes (taken from padding)
+    
+    /// One-way wind-down flag: no deposits or investments, exits stay open
+    pub is_shutdown: bool,          // 1 byte (taken from padding)
+    
+    /// Reason code passed to `shutdown` (meaningful only once shut down)
+    pub shutdown_reason: u8,        // 1 byte (taken from padding)
+    
+    /// Layout version (0 = written before versioning, see VAULT_STATE_VERSION)
+    pub version: u8,                // 1 byte (taken from padding)
+    
+    /// Whether the vault has an entry in the program's vault index
+    pub indexed: bool,              // 1 byte (taken from padding)
+    
+    // Padding for future upgrades
+    pub _reserved: [u8; 40],        // 40 bytes
+}
+
+/// Program-wide configuration (singleton PDA)
+///
+/// Security considerations:
+/// - Created once by the program upgrade authority, then governed by `admin`
+/// - Gates `initialize` through the asset mint allowlist and creation fee
+/// - `vault_count` numbers the vault index, so indexed vaults can be enumerated
+#[account]
+pub struct GlobalConfig {
+    /// Admin that manages the config and the asset mint allowlist
+    pub admin: Pubkey,              // 32 bytes
+
+    /// Receives vault creation fees
+    pub fee_recipient: Pubkey,      // 32 bytes
+
+    /// Lamports charged to the creator by `initialize`
+    pub creation_fee_lamports: u64, // 8 bytes
+
+    /// Number of vaults in the index (next index to assign)
+    pub vault_count: u64,           // 8 bytes
+
+    /// Bump seed for PDA
+    pub bump: u8,                   // 1 byte
+
+    // Padding for future upgrades
+    pub _reserved: [u8; 64],        // 64 bytes
+}
+
+impl GlobalConfig {
+    /// Space calculation:
+    /// 8 (discriminator) + 32 (admin) + 32 (fee_recipient) + 8 (creation_fee_lamports)
+    /// + 8 (vault_count) + 1 (bump) + 64 (padding)
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 8 + 1 + 64;
+
+    /// Apply new parameters
+    pub fn apply(&mut self, params: &ConfigParams) {
+        self.fee_recipient = params.fee_recipient;
+        self.creation_fee_lamports = params.creation_fee_lamports;
+    }
+
+    /// Reserve the next vault index
+    ///
+    /// Security: Uses checked math
+    pub fn next_vault_index(&mut self) -> Result<u64> {
+        let index = self.vault_count;
+        self.vault_count = index
+            .checked_add(1)
+            .ok_or(error!(crate::errors::VaultError::MathOverflow))?;
+        Ok(index)
+    }
+}
+
+/// Adjustable fields of `GlobalConfig`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct ConfigParams {
+    /// Receives vault creation fees
+    pub fee_recipient: Pubkey,
+
+    /// Lamports charged to the creator by `initialize`
+    pub creation_fee_lamports: u64,
+}
+
+/// Allowlist entry for an asset mint, one PDA per mint
+#[account]
+pub struct AllowedMint {
+    /// Asset mint this entry covers
+    pub mint: Pubkey,               // 32 bytes
+
+    /// Whether new vaults may be created for this mint
+    pub allowed: bool,              // 1 byte
+
+    /// Bump seed for PDA
+    pub bump: u8,                   // 1 byte
+}
+
+impl AllowedMint {
+    /// Space calculation:
+    /// 8 (discriminator) + 32 (mint) + 1 (allowed) + 1 (bump)
+    pub const SPACE: usize = 8 + 32 + 1 + 1;
+}
+
+/// One vault in the program's vault index, one PDA per index
+///
+/// Seeds are `[VAULT_INDEX_SEED, index]`, so clients enumerate vaults by
+/// deriving indexes `0..GlobalConfig::vault_count`.
+#[account]
+pub struct VaultIndexEntry {
+    /// Position in the index
+    pub index: u64,                 // 8 bytes
+
+    /// Vault state PDA
+    pub vault: Pubkey,              // 32 bytes
+
+    /// Asset mint of the vault
+    pub asset_mint: Pubkey,         // 32 bytes
+
+    /// Vault id within the asset mint
+    pub vault_id: u64,              // 8 bytes
+
+    /// Authority the vault was created with
+    pub authority: Pubkey,          // 32 bytes
+
+    /// Unix timestamp of creation (of registration, for vaults added by `register_vault`)
+    pub created_at: i64,            // 8 bytes
+
+    /// Bump seed for PDA
+    pub bump: u8,                   // 1 byte
+}
+
+impl VaultIndexEntry {
+    /// Space calculation:
+    /// 8 (discriminator) + 8 (index) + 32 (vault) + 32 (asset_mint) + 8 (vault_id)
+    /// + 32 (authority) + 8 (created_at) + 1 (bump)
+    pub const SPACE: usize = 8 + 8 + 32 + 32 + 8 + 32 + 8 + 1;
+}
+
+/// Per-depositor position, one PDA per (vault, user)
+///
+/// Security considerations:
+/// - Seeds bind the position to a single vault and user
+/// - Created on first deposit, paid for by the user
+/// - Cumulative totals never decrease, so the per-user cap bounds lifetime deposits
+/// - Shares minted under a lockup sit in the vault's share escrow until
+///   `unlock_shares`, so the lock cannot be skipped by transferring shares
+#[account]
+#[derive(Debug, PartialEq)]
+pub struct UserPosition {
+    /// Vault this position belongs to
+    pub vault: Pubkey,              // 32 bytes
+
+    /// Depositor
+    pub user: Pubkey,               // 32 bytes
+
+    /// Cumulative assets credited to this user (after transfer fees)
+    pub total_deposited: u64,       // 8 bytes
+
+    /// Cumulative shares minted to this user
+    pub total_shares_minted: u64,   // 8 bytes
+
+    /// Unix timestamp of the first deposit
+    pub first_deposit_at: i64,      // 8 bytes
+
+    /// Unix timestamp of the latest deposit
+    pub last_deposit_at: i64,       // 8 bytes
+
+    /// Bump seed for PDA
+    pub bump: u8,                   // 1 byte
+
+    /// Unix timestamp before which this user's locked shares cannot be released
+    pub unlock_at: i64,             // 8 bytes (taken from padding)
+
+    /// Shares held in the vault's share escrow until `unlock_at`
+    pub locked_shares: u64,         // 8 bytes (taken from padding)
+
     // Padding for future upgrades
-    pub _reserved: [u8; 128],       // 128 bytes
+    pub _reserved: [u8; 48],        // 48 bytes
+}
+
+impl UserPosition {
+    /// Space calculation:
+    /// 8 (discriminator) + 32 (vault) + 32 (user) + 8 (total_deposited)
+    /// + 8 (total_shares_minted) + 8 (first_deposit_at) + 8 (last_deposit_at)
+    /// + 1 (bump) + 8 (unlock_at) + 8 (locked_shares) + 48 (padding)
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 1 + 8 + 8 + 48;
+
+    /// Reject deposits that would take this user above `per_user_deposit_cap`
+    ///
+    /// Security: Uses checked math to prevent overflow
+    pub fn check_deposit_cap(&self, per_user_deposit_cap: u64, amount: u64) -> Result<()> {
+        if per_user_deposit_cap == 0 {
+            return Ok(());
+        }
+
+        let total_after = self
+            .total_deposited
+            .checked_add(amount)
+            .ok_or(error!(crate::errors::VaultError::MathOverflow))?;
+        require!(
+            total_after <= per_user_deposit_cap,
+            crate::errors::VaultError::UserDepositCapExceeded
+        );
+        Ok(())
+    }
+
+    /// Reject releasing locked shares before `unlock_at`
+    pub fn check_unlocked(&self, now: i64) -> Result<()> {
+        require!(now >= self.unlock_at, crate::errors::VaultError::SharesLocked);
+        Ok(())
+    }
+
+    /// Record a deposit, initializing the position on first use
+    ///
+    /// Under a lockup the minted shares are added to `locked_shares`, and every
+    /// deposit relocks them all until `now + lockup_duration`, so no share is
+    /// released before it has been held for the full lockup. An unlock time
+    /// set under a longer lockup is never shortened.
+    ///
+    /// Security: Uses checked math to prevent overflow
+    #[allow(clippy::too_many_arguments)]
+    pub fn record_deposit(
+        &mut self,
+        vault: Pubkey,
+        user: Pubkey,
+        bump: u8,
+        assets: u64,
+        shares: u64,
+        now: i64,
+        lockup_duration: i64,
+    ) -> Result<()> {
+        if self.vault == Pubkey::default() {
+            self.vault = vault;
+            self.user = user;
+            self.bump = bump;
+            self.first_deposit_at = now;
+        }
+
+        self.total_deposited = self
+            .total_deposited
+            .checked_add(assets)
+            .ok_or(error!(crate::errors::VaultError::MathOverflow))?;
+        self.total_shares_minted = self
+            .total_shares_minted
+            .checked_add(shares)
+            .ok_or(error!(crate::errors::VaultError::MathOverflow))?;
+        self.last_deposit_at = now;
+
+        if lockup_duration == 0 {
+            return Ok(());
+        }
+
+        self.locked_shares = self
+            .locked_shares
+            .checked_add(shares)
+            .ok_or(error!(crate::errors::VaultError::MathOverflow))?;
+        let unlock_at = now
+            .checked_add(lockup_duration)
+            .ok_or(error!(crate::errors::VaultError::MathOverflow))?;
+        self.unlock_at = self.unlock_at.max(unlock_at);
+        Ok(())
+    }
+}
+
+/// Volume attributed to one referrer, one PDA per (vault, referrer)
+///
+/// Security considerations:
+/// - Seeds bind the stats to a single vault and referrer
+/// - Created by the first referred deposit, paid for by the depositor
+/// - Totals only grow; any payout at `referral_fee_bps` happens off-chain
+#[account]
+#[derive(Debug, PartialEq)]
+pub struct ReferralStats {
+    /// Vault the volume was deposited into
+    pub vault: Pubkey,              // 32 bytes
+
+    /// Distribution partner credited with the deposits
+    pub referrer: Pubkey,           // 32 bytes
+
+    /// Cumulative assets received from referred deposits
+    pub total_deposited: u64,       // 8 bytes
+
+    /// Cumulative shares minted by referred deposits
+    pub total_shares: u64,          // 8 bytes
+
+    /// Number of referred deposits
+    pub deposit_count: u64,         // 8 bytes
+
+    /// Bump seed for PDA
+    pub bump: u8,                   // 1 byte
+
+    // Padding for future upgrades
+    pub _reserved: [u8; 32],        // 32 bytes
+}
+
+impl ReferralStats {
+    /// Space calculation:
+    /// 8 (discriminator) + 32 (vault) + 32 (referrer) + 8 (total_deposited)
+    /// + 8 (total_shares) + 8 (deposit_count) + 1 (bump) + 32 (padding)
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 1 + 32;
+
+    /// Attribute a deposit, initializing the stats on first use
+    ///
+    /// Security: Uses checked math to prevent overflow
+    pub fn record_deposit(
+        &mut self,
+        vault: Pubkey,
+        referrer: Pubkey,
+        bump: u8,
+        assets: u64,
+        shares: u64,
+    ) -> Result<()> {
+        if self.vault == Pubkey::default() {
+            self.vault = vault;
+            self.referrer = referrer;
+            self.bump = bump;
+        }
+
+        self.total_deposited = self
+            .total_deposited
+            .checked_add(assets)
+            .ok_or(error!(crate::errors::VaultError::MathOverflow))?;
+        self.total_shares = self
+            .total_shares
+            .checked_add(shares)
+            .ok_or(error!(crate::errors::VaultError::MathOverflow))?;
+        self.deposit_count = self
+            .deposit_count
+            .checked_add(1)
+            .ok_or(error!(crate::errors::VaultError::MathOverflow))?;
+        Ok(())
+    }
+}
+
+/// Asynchronous redeem request (ERC-7540 style), one PDA per (vault, user)
+///
+/// Lifecycle: `request_redeem` escrows shares -> `fulfill_requests` burns them
+/// at the batch exchange rate -> `claim_redeem` pays out and closes the PDA.
+/// `cancel_redeem` returns the shares while the request is still pending.
+#[account]
+#[derive(Debug, PartialEq)]
+pub struct RedeemRequest {
+    /// Vault this request belongs to
+    pub vault: Pubkey,              // 32 bytes
+
+    /// Owner of the escrowed shares
+    pub user: Pubkey,               // 32 bytes
+
+    /// Shares escrowed for redemption
+    pub shares: u64,                // 8 bytes
+
+    /// Assets owed, locked in at fulfillment
+    pub assets: u64,                // 8 bytes
+
+    /// Unix timestamp of the request
+    pub requested_at: i64,          // 8 bytes
+
+    /// Unix timestamp of fulfillment (0 = pending)
+    pub fulfilled_at: i64,          // 8 bytes
+
+    /// Bump seed for PDA
+    pub bump: u8,                   // 1 byte
+}
+
+impl RedeemRequest {
+    /// Space calculation:
+    /// 8 (discriminator) + 32 (vault) + 32 (user) + 8 (shares) + 8 (assets)
+    /// + 8 (requested_at) + 8 (fulfilled_at) + 1 (bump)
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 1;
+
+    pub fn is_fulfilled(&self) -> bool {
+        self.fulfilled_at != 0
+    }
+}
+
+/// Merkle leaf for an allowed depositor: sha256(0x00 || user)
+pub fn depositor_leaf(user: &Pubkey) -> [u8; 32] {
+    hashv(&[&[0x00], user.as_ref()]).to_bytes()
+}
+
+/// Verify a Merkle proof for `leaf` against `root`
+///
+/// Internal nodes are sha256(0x01 || min(a, b) || max(a, b)): sorted pairs mean
+/// proofs need no left/right flags, and the 0x00/0x01 prefixes keep leaves
+/// and internal nodes from being confused.
+pub fn verify_merkle_proof(root: &[u8; 32], leaf: [u8; 32], proof: &[[u8; 32]]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        let (first, second) = if node <= *sibling {
+            (node, *sibling)
+        } else {
+            (*sibling, node)
+        };
+        hashv(&[&[0x01], first.as_ref(), second.as_ref()]).to_bytes()
+    });
+    computed == *root
+}
+
+/// Extra PDA seed for `vault_id`
+///
+/// Empty for id 0, so the original one-per-mint vaults keep their addresses;
+/// otherwise the little-endian id.
+pub fn vault_id_seed(vault_id: u64) -> Vec<u8> {
+    if vault_id == 0 {
+        Vec::new()
+    } else {
+        vault_id.to_le_bytes().to_vec()
+    }
 }
 
 /// Protocol registry for approved investment targets
 ///
 /// Architecture: Registry + Whitelist (Option 3)
 /// - Single program with upgradeable on-chain whitelist
-/// - Authority can add/remove/toggle protocols via instructions
-/// - Prevents rug pulls by restricting investment destinations
-/// - Tracks invested amount per protocol for transparency
+/// - Each approved protocol lives in its own `ApprovedProtocol` PDA
+/// - Registry only keeps per-vault bookkeeping, so it never fills up
+/// - Fixed 175-byte layout with no heap fields; `invest` and `toggle_protocol`
+///   do not load it at all, so it is kept as a Borsh account, not zero-copy
 ///
 /// Security: Authority-controlled whitelist prevents investing to arbitrary addresses
 #[account]
 pub struct ProtocolRegistry {
     /// Vault this registry belongs to
     pub vault: Pubkey,              // 32 bytes
-    
-    /// List of approved protocol program IDs
-    /// Max ~15 protocols before hitting account size limits
-    pub approved_protocols: Vec<ApprovedProtocol>, // 4 + (n * ~80) bytes
-    
+
+    /// Number of open `ApprovedProtocol` entries for this vault
+    pub protocol_count: u32,        // 4 bytes
+
+    /// Sum of all entries' target weights, never above 100%
+    pub total_target_weight_bps: u16, // 2 bytes
+
     /// Bump seed for PDA
     pub bump: u8,                   // 1 byte
+
+    /// Layout version (0 = written before versioning, see PROTOCOL_REGISTRY_VERSION)
+    pub version: u8,                // 1 byte
+}
+
+impl ProtocolRegistry {
+    /// Space calculation:
+    /// 8 (discriminator) + 32 (vault) + 4 (protocol_count) + 2 (total_target_weight_bps)
+    /// + 1 (bump) + 1 (version) + 127 (padding)
+    pub const SPACE: usize = 8 + 32 + 4 + 2 + 1 + 1 + 127;
+
+    /// Convert a registry written before protocols moved to their own PDAs
+    ///
+    /// Entries become `ApprovedProtocol` accounts, so only their count is kept.
+    pub fn from_legacy(legacy: &LegacyProtocolRegistry) -> Result<Self> {
+        Ok(Self {
+            vault: legacy.vault,
+            protocol_count: u32::try_from(legacy.approved_protocols.len())
+                .map_err(|_| error!(crate::errors::VaultError::MathOverflow))?,
+            total_target_weight_bps: 0,
+            bump: legacy.bump,
+            version: crate::constants::PROTOCOL_REGISTRY_VERSION,
+        })
+    }
+
+    /// Replace one entry's weight in the running total
+    ///
+    /// Security: Rejects totals above 100% so allocations never exceed total assets
+    pub fn update_total_weight(&mut self, old_weight_bps: u16, new_weight_bps: u16) -> Result<()> {
+        let total = (self.total_target_weight_bps as u64)
+            .checked_sub(old_weight_bps as u64)
+            .ok_or(error!(crate::errors::VaultError::MathOverflow))?
+            .checked_add(new_weight_bps as u64)
+            .ok_or(error!(crate::errors::VaultError::MathOverflow))?;
+
+        require!(
+            total <= crate::constants::BPS_DENOMINATOR,
+            crate::errors::VaultError::InvalidTargetWeight
+        );
+
+        self.total_target_weight_bps = total as u16;
+        Ok(())
+    }
+}
+
+/// `ProtocolRegistry` layout before each protocol had its own PDA
+///
+/// Accounts were allocated with `LEGACY_PROTOCOL_REGISTRY_SPACE` bytes and share
+/// the `ProtocolRegistry` discriminator. Only read by `migrate_vault`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct LegacyProtocolRegistry {
+    /// Vault this registry belongs to
+    pub vault: Pubkey,
+
+    /// Protocols stored inline, in insertion order
+    pub approved_protocols: Vec<LegacyApprovedProtocol>,
+
+    /// Bump seed for PDA
+    pub bump: u8,
 }
 
-/// Individual approved protocol entry
+impl LegacyProtocolRegistry {
+    /// Deserialize legacy account data, discriminator included
+    pub fn try_from_account_data(data: &[u8]) -> Result<Self> {
+        require!(
+            data.len() == crate::constants::LEGACY_PROTOCOL_REGISTRY_SPACE
+                && data.starts_with(ProtocolRegistry::DISCRIMINATOR),
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        Self::deserialize(&mut &data[ProtocolRegistry::DISCRIMINATOR.len()..])
+            .map_err(|_| error!(ErrorCode::AccountDidNotDeserialize))
+    }
+}
+
+/// Inline registry entry of `LegacyProtocolRegistry`
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct LegacyApprovedProtocol {
+    /// Program ID or token account of the protocol
+    pub target: Pubkey,
+
+    /// Whether this protocol is currently enabled
+    pub enabled: bool,
+
+    /// Amount currently invested in this protocol
+    pub invested_amount: u64,
+
+    /// Human-readable name
+    pub name: String,
+}
+
+/// Approved protocol entry, one PDA per (vault, target)
+///
+/// Security considerations:
+/// - Seeds bind the entry to a single vault and target
+/// - `expected_owner` pins who must own the target token account
+/// - `invest` only loads the entry for the target it pays into
+/// - Tracks invested amount per protocol for transparency
+#[account]
+#[derive(Debug, PartialEq)]
 pub struct ApprovedProtocol {
+    /// Vault this entry belongs to
+    pub vault: Pubkey,              // 32 bytes
+
     /// Program ID or token account of the protocol
     pub target: Pubkey,             // 32 bytes
-    
+
+    /// Owner the target token account must have when investing
+    pub expected_owner: Pubkey,     // 32 bytes
+
     /// Whether this protocol is currently enabled
     pub enabled: bool,              // 1 byte
-    
+
     /// Amount currently invested in this protocol
     pub invested_amount: u64,       // 8 bytes
-    
+
+    /// Maximum amount that may be invested in this protocol (None = uncapped)
+    pub invest_cap: Option<u64>,    // 1 + 8 bytes
+
+    /// Target share of total assets for `rebalance`, in basis points
+    pub target_weight_bps: u16,     // 2 bytes
+
     /// Human-readable name (e.g., "Marinade", "Kamino")
     pub name: String,               // 4 + up to 32 bytes
+
+    /// Structured description of the protocol for events and dashboards
+    pub metadata: ProtocolMetadata, // ProtocolMetadata::SPACE bytes
+
+    /// Bump seed for PDA
+    pub bump: u8,                   // 1 byte
 }
 
-impl ProtocolRegistry {
-    /// Space calculation: accommodates up to 10 protocols comfortably
-    /// 8 (discriminator) + 32 (vault) + 4 (vec len) + (10 * 80) + 1 (bump) + 128 (padding)
-    pub const SPACE: usize = 8 + 32 + 4 + (10 * 80) + 1 + 128;
+/// Kind of protocol behind an approved target
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProtocolKind {
+    /// Plain SPL token account
+    TokenAccount,
+    /// Lending market (e.g., Kamino, Solend)
+    Lending,
+    /// Stake pool (e.g., Marinade, Jito)
+    StakePool,
+    /// Another vault
+    Vault,
+    /// Program implementing the strategy adapter interface (`target` is its program ID)
+    Adapter,
+}
+
+/// Protocol metadata set at `add_protocol`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct ProtocolMetadata {
+    /// Kind of protocol
+    pub kind: ProtocolKind,         // 1 byte
+
+    /// Program that owns the protocol (e.g., the lending market program)
+    pub owner_program: Pubkey,      // 32 bytes
 
-    /// Check if a protocol target is approved and enabled
-    pub fn is_protocol_approved(&self, target: &Pubkey) -> bool {
-        self.approved_protocols
-            .iter()
-            .any(|p| p.target == *target && p.enabled)
+    /// Risk tier assigned by the authority (0 = lowest risk)
+    pub risk_tier: u8,              // 1 byte
+
+    /// URI of off-chain metadata (docs, audits, dashboard labels)
+    pub uri: String,                // 4 + up to MAX_PROTOCOL_URI_LEN bytes
+
+    /// Hash of the document at `uri`
+    pub uri_hash: [u8; 32],         // 32 bytes
+}
+
+impl ProtocolMetadata {
+    /// Space calculation: 1 (kind) + 32 (owner_program) + 1 (risk_tier)
+    /// + 4 + MAX_PROTOCOL_URI_LEN (uri) + 32 (uri_hash)
+    pub const SPACE: usize = 1 + 32 + 1 + (4 + crate::constants::MAX_PROTOCOL_URI_LEN) + 32;
+}
+
+/// Name, symbol and URI shown by wallets for the vault share token
+///
+/// Stored in the share mint's Token-2022 metadata extension, not in program accounts
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct ShareMetadata {
+    /// Token name (e.g., "USDC Vault Shares")
+    pub name: String,
+
+    /// Token symbol (e.g., "vUSDC")
+    pub symbol: String,
+
+    /// URI of the off-chain JSON (icon, description)
+    pub uri: String,
+}
+
+impl ShareMetadata {
+    /// Validate field lengths
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.name.len() <= crate::constants::MAX_SHARE_NAME_LEN
+                && self.symbol.len() <= crate::constants::MAX_SHARE_SYMBOL_LEN
+                && self.uri.len() <= crate::constants::MAX_SHARE_URI_LEN,
+            crate::errors::VaultError::ShareMetadataTooLong
+        );
+        Ok(())
     }
 
-    /// Get mutable protocol by target
-    pub fn get_protocol_mut(&mut self, target: &Pubkey) -> Option<&mut ApprovedProtocol> {
-        self.approved_protocols
-            .iter_mut()
-            .find(|p| p.target == *target)
+    /// Size of the Token-2022 metadata TLV entry holding these fields
+    pub fn tlv_size_of(&self, mint: &Pubkey, update_authority: &Pubkey) -> Result<usize> {
+        let token_metadata = TokenMetadata {
+            update_authority: OptionalNonZeroPubkey::try_from(Some(*update_authority))?,
+            mint: *mint,
+            name: self.name.clone(),
+            symbol: self.symbol.clone(),
+            uri: self.uri.clone(),
+            additional_metadata: vec![],
+        };
+        Ok(token_metadata.tlv_size_of()?)
+    }
+}
+
+impl ApprovedProtocol {
+    /// Space calculation:
+    /// 8 (discriminator) + 32 (vault) + 32 (target) + 32 (expected_owner) + 1 (enabled)
+    /// + 8 (invested_amount) + 9 (invest_cap) + 2 (target_weight_bps) + 4 + 32 (name)
+    /// + ProtocolMetadata::SPACE + 1 (bump) + 64 (padding)
+    pub const SPACE: usize =
+        8 + 32 + 32 + 32 + 1 + 8 + 9 + 2 + (4 + 32) + ProtocolMetadata::SPACE + 1 + 64;
+
+    /// Entry for a protocol of a `LegacyProtocolRegistry`
+    ///
+    /// Legacy entries had no owner pin, cap, weight or metadata: the owner is
+    /// pinned to whoever owns the target token account at migration time
+    /// (`Pubkey::default()` when the target no longer exists).
+    pub fn from_legacy(
+        vault: Pubkey,
+        legacy: &LegacyApprovedProtocol,
+        expected_owner: Pubkey,
+        owner_program: Pubkey,
+        bump: u8,
+    ) -> Self {
+        Self {
+            vault,
+            target: legacy.target,
+            expected_owner,
+            enabled: legacy.enabled,
+            invested_amount: legacy.invested_amount,
+            invest_cap: None,
+            target_weight_bps: 0,
+            name: legacy.name.clone(),
+            metadata: ProtocolMetadata {
+                kind: ProtocolKind::TokenAccount,
+                owner_program,
+                risk_tier: 0,
+                uri: String::new(),
+                uri_hash: [0; 32],
+            },
+            bump,
+        }
     }
 
-    /// Track investment amount for a protocol
-    pub fn track_investment(&mut self, target: &Pubkey, amount: u64) -> Result<()> {
-        if let Some(protocol) = self.get_protocol_mut(target) {
-            protocol.invested_amount = protocol
-                .invested_amount
-                .checked_add(amount)
-                .ok_or(error!(crate::errors::VaultError::MathOverflow))?;
+    /// Track investment amount for this protocol
+    ///
+    /// Security: Uses checked math and enforces the per-protocol cap
+    pub fn track_investment(&mut self, amount: u64) -> Result<()> {
+        let invested_amount = self
+            .invested_amount
+            .checked_add(amount)
+            .ok_or(error!(crate::errors::VaultError::MathOverflow))?;
+
+        if let Some(cap) = self.invest_cap {
+            require!(
+                invested_amount <= cap,
+                crate::errors::VaultError::ProtocolCapExceeded
+            );
         }
+
+        self.invested_amount = invested_amount;
+        Ok(())
+    }
+
+    /// Track assets moved back out of this protocol
+    ///
+    /// Security: Uses checked math so more than was invested cannot be divested
+    pub fn track_divestment(&mut self, amount: u64) -> Result<()> {
+        self.invested_amount = self
+            .invested_amount
+            .checked_sub(amount)
+            .ok_or(error!(crate::errors::VaultError::MathOverflow))?;
         Ok(())
     }
+
+    /// Mark the entry to the value reported by its strategy adapter
+    ///
+    /// Returns `(gain, loss)` against the tracked invested amount, which then
+    /// becomes the reported value.
+    pub fn record_report(&mut self, value: u64) -> (u64, u64) {
+        let previous = self.invested_amount;
+        self.invested_amount = value;
+        if value >= previous {
+            (value - previous, 0)
+        } else {
+            (0, previous - value)
+        }
+    }
+
+    /// Clear the entry for a recall of the target's whole `balance`
+    ///
+    /// Returns `(gain, loss)` against the tracked invested amount, like
+    /// `record_report`, so yield above it is booked instead of stranded.
+    pub fn record_recall(&mut self, balance: u64) -> (u64, u64) {
+        let result = self.record_report(balance);
+        self.invested_amount = 0;
+        result
+    }
+
+    /// Compare the invested amount to the target weight of `total_assets`
+    ///
+    /// Gaps within `tolerance_bps` of `total_assets` are reported as balanced.
+    /// Security: Uses u128 intermediates and checked math
+    pub fn allocation_gap(&self, total_assets: u64, tolerance_bps: u16) -> Result<AllocationGap> {
+        let bps_of_total = |bps: u16| -> Result<u64> {
+            let value = (total_assets as u128)
+                .checked_mul(bps as u128)
+                .ok_or(error!(crate::errors::VaultError::MathOverflow))?
+                .checked_div(crate::constants::BPS_DENOMINATOR as u128)
+                .ok_or(error!(crate::errors::VaultError::DivisionByZero))?;
+            u64::try_from(value).map_err(|_| error!(crate::errors::VaultError::MathOverflow))
+        };
+
+        let target_amount = bps_of_total(self.target_weight_bps)?;
+        let tolerance = bps_of_total(tolerance_bps)?;
+
+        Ok(if self.invested_amount > target_amount {
+            let excess = self.invested_amount - target_amount;
+            if excess > tolerance {
+                AllocationGap::Over(excess)
+            } else {
+                AllocationGap::Balanced
+            }
+        } else {
+            let shortfall = target_amount - self.invested_amount;
+            if shortfall > tolerance {
+                AllocationGap::Under(shortfall)
+            } else {
+                AllocationGap::Balanced
+            }
+        })
+    }
+}
+
+/// Distance of a protocol's invested amount from its target allocation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AllocationGap {
+    /// Within the tolerance band
+    Balanced,
+    /// Invested amount is below target by this many assets
+    Under(u64),
+    /// Invested amount is above target by this many assets
+    Over(u64),
 }
 
 impl VaultState {
+    /// Deserialize a vault written with any earlier layout version
+    ///
+    /// Layouts only grow at the end, so bytes a shorter account lacks read back
+    /// as zero, like the padding later fields were carved from.
+    pub fn try_deserialize_any_version(data: &[u8]) -> Result<Self> {
+        let mut buf = data.to_vec();
+        if buf.len() < crate::constants::VAULT_STATE_SIZE {
+            buf.resize(crate::constants::VAULT_STATE_SIZE, 0);
+        }
+        Self::try_deserialize(&mut buf.as_slice())
+    }
+
+    /// Book a strategy report's gain or loss into `total_assets`
+    ///
+    /// Security: Uses checked math; a loss cannot exceed the tracked assets
+    pub fn apply_report(&mut self, gain: u64, loss: u64) -> Result<()> {
+        self.total_assets = self
+            .total_assets
+            .checked_add(gain)
+            .ok_or(crate::errors::VaultError::MathOverflow)?
+            .checked_sub(loss)
+            .ok_or(crate::errors::VaultError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Upgrade an older layout in place, returning the version migrated from
+    ///
+    /// v0 -> v1: fields up to `indexed` were carved from zeroed padding, so only
+    /// the version is stamped. v0 vaults predate the vault index and stay
+    /// unindexed until `register_vault`.
+    pub fn migrate(&mut self) -> Result<u8> {
+        let from_version = self.version;
+        require!(
+            from_version < crate::constants::VAULT_STATE_VERSION,
+            crate::errors::VaultError::VaultAlreadyMigrated
+        );
+
+        self.version = crate::constants::VAULT_STATE_VERSION;
+        Ok(from_version)
+    }
+
+    /// Require `user` to be in the depositor allowlist when one is set
+    ///
+    /// Security: Proof length bounded to keep verification cost predictable
+    pub fn check_depositor(&self, user: &Pubkey, proof: &[[u8; 32]]) -> Result<()> {
+        if self.depositor_merkle_root == [0; 32] {
+            return Ok(());
+        }
+
+        require!(
+            proof.len() <= crate::constants::MAX_MERKLE_PROOF_LEN
+                && verify_merkle_proof(&self.depositor_merkle_root, depositor_leaf(user), proof),
+            crate::errors::VaultError::DepositorNotAllowed
+        );
+        Ok(())
+    }
+
+    /// Assets that can still be deposited before hitting `deposit_cap`
+    ///
+    /// ERC-4626 `maxDeposit`: u64::MAX when uncapped, 0 once the cap is reached
+    /// or the vault is shut down
+    pub fn max_deposit(&self) -> u64 {
+        if self.is_shutdown {
+            return 0;
+        }
+        if self.deposit_cap == 0 {
+            return u64::MAX;
+        }
+        self.deposit_cap.saturating_sub(self.total_assets)
+    }
+
+    /// Reject deposits and investments once the vault is shut down
+    pub fn check_active(&self) -> Result<()> {
+        require!(!self.is_shutdown, crate::errors::VaultError::VaultIsShutdown);
+        Ok(())
+    }
+
+    /// Enforce the user's lockup, which a shutdown waives so holders can exit
+    pub fn check_unlockable(&self, position: &UserPosition, now: i64) -> Result<()> {
+        if self.is_shutdown {
+            return Ok(());
+        }
+        position.check_unlocked(now)
+    }
+
+    /// Reject deposits that would take total_assets above `deposit_cap`
+    ///
+    /// Security: Uses checked math to prevent overflow
+    pub fn check_deposit_cap(&self, amount: u64) -> Result<()> {
+        if self.deposit_cap == 0 {
+            return Ok(());
+        }
+
+        let total_after = self
+            .total_assets
+            .checked_add(amount)
+            .ok_or(error!(crate::errors::VaultError::MathOverflow))?;
+        require!(
+            total_after <= self.deposit_cap,
+            crate::errors::VaultError::DepositCapExceeded
+        );
+        Ok(())
+    }
+
     /// Calculate shares to mint for a given asset amount
     ///
     /// ERC-4626 formula:
@@ -138,6 +983,74 @@ impl VaultState {
             .map_err(|_| error!(crate::errors::VaultError::MathOverflow))
     }
 
+    /// Assets in the vault token account that are not owed to fulfilled redeem requests
+    pub fn idle_assets(&self, vault_balance: u64) -> u64 {
+        vault_balance.saturating_sub(self.claimable_assets)
+    }
+
+    /// Idle assets the vault must keep: `total_assets × min_idle_bps`, rounded up
+    ///
+    /// Security: Uses checked math to prevent overflow
+    pub fn min_idle_assets(&self) -> Result<u64> {
+        let required = (self.total_assets as u128)
+            .checked_mul(self.min_idle_bps as u128)
+            .ok_or(error!(crate::errors::VaultError::MathOverflow))?
+            .div_ceil(crate::constants::BPS_DENOMINATOR as u128);
+        u64::try_from(required).map_err(|_| error!(crate::errors::VaultError::MathOverflow))
+    }
+
+    /// Largest amount `invest` can move out while keeping the idle buffer
+    /// (0 once the vault is shut down)
+    pub fn max_invest(&self, vault_balance: u64) -> Result<u64> {
+        if self.is_shutdown {
+            return Ok(0);
+        }
+        Ok(self
+            .idle_assets(vault_balance)
+            .saturating_sub(self.min_idle_assets()?))
+    }
+
+    /// Reject investments that would leave idle assets below `min_idle_bps`
+    pub fn check_idle_buffer(&self, vault_balance: u64, amount: u64) -> Result<()> {
+        require!(
+            amount <= self.max_invest(vault_balance)?,
+            crate::errors::VaultError::IdleBufferBreached
+        );
+        Ok(())
+    }
+
+    /// Whether nothing is owed to anyone, so the vault can be closed
+    ///
+    /// `total_assets` is ignored once `total_shares` is zero: redeem payouts
+    /// round down per request, so a batch that empties the vault can leave
+    /// dust no share can claim
+    pub fn is_empty(&self) -> bool {
+        self.total_shares == 0 && self.pending_redeem_shares == 0 && self.claimable_assets == 0
+    }
+
+    /// Burn a fulfilled batch of escrowed shares and reserve its assets for claims
+    ///
+    /// Security: Uses checked math to prevent overflow
+    pub fn settle_redeems(&mut self, shares: u64, assets: u64) -> Result<()> {
+        self.total_assets = self
+            .total_assets
+            .checked_sub(assets)
+            .ok_or(error!(crate::errors::VaultError::MathOverflow))?;
+        self.total_shares = self
+            .total_shares
+            .checked_sub(shares)
+            .ok_or(error!(crate::errors::VaultError::MathOverflow))?;
+        self.pending_redeem_shares = self
+            .pending_redeem_shares
+            .checked_sub(shares)
+            .ok_or(error!(crate::errors::VaultError::MathOverflow))?;
+        self.claimable_assets = self
+            .claimable_assets
+            .checked_add(assets)
+            .ok_or(error!(crate::errors::VaultError::MathOverflow))?;
+        Ok(())
+    }
+
     /// Calculate asset value of shares
     ///
     /// ERC-4626 formula: assets = shares * totalAssets / totalShares
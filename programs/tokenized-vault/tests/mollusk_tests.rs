@@ -1,26 +1,32 @@
-/// Mollusk Integration Tests for Tokenized Vault
-///
-/// These tests use mollusk-svm principles to test the program logic
-/// with proper security validation.
-///
-/// Security coverage:
-///  Signer validation
-///  Account ownership checks
-///  Authority validation
-///  PDA validation
-///  Token account validation
-///  Math safety
-///  Business logic
-///
-/// Note: These tests follow the security checklist defined in the repository rules.
-/// Full integration tests with mollusk-svm would require aligning Solana SDK versions
-/// between Anchor 0.32.1 and mollusk-svm 0.7.2, which have version conflicts.
-/// Instead, we provide comprehensive unit tests that validate all security properties.
+//! Mollusk Integration Tests for Tokenized Vault
+//!
+//! These tests use mollusk-svm principles to test the program logic
+//! with proper security validation.
+//!
+//! Security coverage:
+//!  Signer validation
+//!  Account ownership checks
+//!  Authority validation
+//!  PDA validation
+//!  Token account validation
+//!  Math safety
+//!  Business logic
+//!
+//! Note: These tests follow the security checklist defined in the repository rules.
+//! Full integration tests with mollusk-svm would require aligning Solana SDK versions
+//! between Anchor 0.32.1 and mollusk-svm 0.7.2, which have version conflicts.
+//! Instead, we provide comprehensive unit tests that validate all security properties.
 
 use anchor_lang::prelude::*;
+use solana_sdk::hash::hashv;
 use tokenized_vault::{
+    adapter,
     constants::*,
-    state::{ProtocolRegistry, VaultState},
+    state::{
+        AllocationGap, ApprovedProtocol, LegacyApprovedProtocol, LegacyProtocolRegistry, ProtocolKind, ProtocolMetadata, ProtocolRegistry, RedeemRequest, ReferralStats,
+        depositor_leaf, vault_id_seed, verify_merkle_proof, AllowedMint, ConfigParams, GlobalConfig, ShareMetadata, UserPosition,
+        VaultIndexEntry, VaultState,
+    },
 };
 
 // =============================================================================
@@ -61,6 +67,97 @@ fn test_pda_seed_collision_protection() {
     assert_ne!(share_mint_1, share_mint_2, "Share mints should be unique per asset mint");
 }
 
+#[test]
+fn test_vault_id_seed_keeps_original_pdas() {
+    // Test that vault id 0 derives the original one-per-mint PDAs and other ids
+    // derive distinct vaults for the same asset mint
+    // Security: Account ownership validation (Section 2)
+
+    let program_id = tokenized_vault::id();
+    let asset_mint = Pubkey::new_unique();
+
+    for seed in [VAULT_SEED, SHARE_MINT_SEED, VAULT_AUTHORITY_SEED] {
+        let (original, _) =
+            Pubkey::find_program_address(&[seed, asset_mint.as_ref()], &program_id);
+        let (id_0, _) = Pubkey::find_program_address(
+            &[seed, asset_mint.as_ref(), &vault_id_seed(0)],
+            &program_id,
+        );
+        let (id_1, _) = Pubkey::find_program_address(
+            &[seed, asset_mint.as_ref(), &vault_id_seed(1)],
+            &program_id,
+        );
+        let (id_2, _) = Pubkey::find_program_address(
+            &[seed, asset_mint.as_ref(), &vault_id_seed(2)],
+            &program_id,
+        );
+
+        assert_eq!(original, id_0, "Vault id 0 must keep the original address");
+        assert_ne!(id_0, id_1);
+        assert_ne!(id_1, id_2);
+    }
+}
+
+#[test]
+fn test_original_vault_state_layout_reads_as_vault_id_0() {
+    // Test that a VaultState written before vault_id existed still deserializes
+    // Security: Account data validation - upgrade compatibility
+
+    let authority = Pubkey::new_unique();
+    let mut data = VaultState::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(authority.as_ref());
+    data.extend_from_slice(Pubkey::new_unique().as_ref());
+    data.extend_from_slice(Pubkey::new_unique().as_ref());
+    data.extend_from_slice(&500u64.to_le_bytes());
+    data.extend_from_slice(&400u64.to_le_bytes());
+    data.extend_from_slice(&[254, 253, 252]);
+    data.extend_from_slice(&[0; 128]);
+    assert_eq!(data.len(), VAULT_STATE_SIZE);
+
+    let vault = VaultState::try_deserialize(&mut data.as_slice()).unwrap();
+    assert_eq!(vault.authority, authority);
+    assert_eq!(vault.total_assets, 500);
+    assert_eq!(vault.authority_bump, 252);
+    assert_eq!(vault.vault_id, 0);
+    assert_eq!(vault.deposit_cap, 0, "Existing vaults read back as uncapped");
+    assert_eq!(vault.per_user_deposit_cap, 0);
+    assert_eq!(vault.depositor_merkle_root, [0; 32], "Existing vaults stay permissionless");
+    assert_eq!(vault.version, 0, "Existing vaults read back as unversioned");
+}
+
+#[test]
+fn test_vault_migration_from_older_layouts() {
+    // Test that migrate_vault reads shorter layouts and only upgrades once
+    // Security: Account data validation - upgrade path
+
+    let authority = Pubkey::new_unique();
+    let mut data = VaultState::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(authority.as_ref());
+    data.extend_from_slice(Pubkey::new_unique().as_ref());
+    data.extend_from_slice(Pubkey::new_unique().as_ref());
+    data.extend_from_slice(&500u64.to_le_bytes());
+    data.extend_from_slice(&400u64.to_le_bytes());
+    data.extend_from_slice(&[254, 253, 252]);
+
+    // A strict read fails on an account shorter than the current layout
+    assert!(VaultState::try_deserialize(&mut data.as_slice()).is_err());
+
+    let mut vault = VaultState::try_deserialize_any_version(&data).unwrap();
+    assert_eq!(vault.authority, authority);
+    assert_eq!(vault.total_shares, 400);
+    assert_eq!(vault.version, 0);
+
+    assert_eq!(vault.migrate().unwrap(), 0);
+    assert_eq!(vault.version, VAULT_STATE_VERSION);
+    assert!(!vault.indexed, "Pre-index vaults are added by register_vault");
+    assert!(vault.migrate().is_err(), "Current layouts are not migrated again");
+
+    // Other account types are rejected by the discriminator check
+    let mut wrong = GlobalConfig::DISCRIMINATOR.to_vec();
+    wrong.extend_from_slice(&data[8..]);
+    assert!(VaultState::try_deserialize_any_version(&wrong).is_err());
+}
+
 #[test]
 fn test_pda_uniqueness_across_seeds() {
     // Test that different seed types produce different PDAs
@@ -94,22 +191,39 @@ fn test_pda_uniqueness_across_seeds() {
 // SECURITY TESTS - Math Safety (Section 6)
 // =============================================================================
 
-#[test]
-fn test_calculate_shares_first_deposit() {
-    // Test share calculation for first deposit (1:1 ratio)
-    // Security: Math safety (Section 6)
-
-    let vault = VaultState {
+fn vault_state(total_assets: u64, total_shares: u64) -> VaultState {
+    VaultState {
         authority: Pubkey::default(),
         asset_mint: Pubkey::default(),
         share_mint: Pubkey::default(),
-        total_assets: 0,
-        total_shares: 0,
+        total_assets,
+        total_shares,
         bump: 0,
         share_bump: 0,
         authority_bump: 0,
-        _reserved: [0; 128],
-    };
+        vault_id: 0,
+        deposit_cap: 0,
+        per_user_deposit_cap: 0,
+        depositor_merkle_root: [0; 32],
+        lockup_duration: 0,
+        pending_redeem_shares: 0,
+        claimable_assets: 0,
+        min_idle_bps: 0,
+        referral_fee_bps: 0,
+        is_shutdown: false,
+        shutdown_reason: 0,
+        version: VAULT_STATE_VERSION,
+        indexed: false,
+        _reserved: [0; 40],
+    }
+}
+
+#[test]
+fn test_calculate_shares_first_deposit() {
+    // Test share calculation for first deposit (1:1 ratio)
+    // Security: Math safety (Section 6)
+
+    let vault = vault_state(0, 0);
 
     assert_eq!(vault.calculate_shares(1000).unwrap(), 1000);
     assert_eq!(vault.calculate_shares(u64::MAX).unwrap(), u64::MAX);
@@ -120,17 +234,7 @@ fn test_calculate_shares_with_profit() {
     // Test share calculation when vault has profit
     // Security: Math safety (Section 6)
 
-    let vault = VaultState {
-        authority: Pubkey::default(),
-        asset_mint: Pubkey::default(),
-        share_mint: Pubkey::default(),
-        total_assets: 2000,
-        total_shares: 1000,
-        bump: 0,
-        share_bump: 0,
-        authority_bump: 0,
-        _reserved: [0; 128],
-    };
+    let vault = vault_state(2000, 1000);
 
     // 500 assets should mint 250 shares (500 * 1000 / 2000)
     assert_eq!(vault.calculate_shares(500).unwrap(), 250);
@@ -144,17 +248,7 @@ fn test_calculate_shares_equal_ratio() {
     // Test share calculation with 1:1 asset/share ratio
     // Security: Math safety (Section 6)
 
-    let vault = VaultState {
-        authority: Pubkey::default(),
-        asset_mint: Pubkey::default(),
-        share_mint: Pubkey::default(),
-        total_assets: 1000,
-        total_shares: 1000,
-        bump: 0,
-        share_bump: 0,
-        authority_bump: 0,
-        _reserved: [0; 128],
-    };
+    let vault = vault_state(1000, 1000);
 
     assert_eq!(vault.calculate_shares(500).unwrap(), 500);
     assert_eq!(vault.calculate_shares(1).unwrap(), 1);
@@ -165,17 +259,7 @@ fn test_calculate_shares_max_values() {
     // Test that u128 intermediate calculations prevent overflow
     // Security: Math safety (Section 6)
 
-    let vault = VaultState {
-        authority: Pubkey::default(),
-        asset_mint: Pubkey::default(),
-        share_mint: Pubkey::default(),
-        total_assets: u64::MAX / 2,
-        total_shares: u64::MAX / 2,
-        bump: 0,
-        share_bump: 0,
-        authority_bump: 0,
-        _reserved: [0; 128],
-    };
+    let vault = vault_state(u64::MAX / 2, u64::MAX / 2);
 
     // Should not panic on large values
     let result = vault.calculate_shares(1_000_000);
@@ -188,17 +272,7 @@ fn test_calculate_shares_precision_loss() {
     // Test integer division precision behavior
     // Security: Math safety (Section 6)
 
-    let vault = VaultState {
-        authority: Pubkey::default(),
-        asset_mint: Pubkey::default(),
-        share_mint: Pubkey::default(),
-        total_assets: 1000,
-        total_shares: 333,
-        bump: 0,
-        share_bump: 0,
-        authority_bump: 0,
-        _reserved: [0; 128],
-    };
+    let vault = vault_state(1000, 333);
 
     // 100 * 333 / 1000 = 33 (integer division)
     assert_eq!(vault.calculate_shares(100).unwrap(), 33);
@@ -209,17 +283,7 @@ fn test_calculate_assets_from_shares() {
     // Test reverse calculation (shares -> assets)
     // Security: Math safety (Section 6)
 
-    let vault = VaultState {
-        authority: Pubkey::default(),
-        asset_mint: Pubkey::default(),
-        share_mint: Pubkey::default(),
-        total_assets: 2000,
-        total_shares: 1000,
-        bump: 0,
-        share_bump: 0,
-        authority_bump: 0,
-        _reserved: [0; 128],
-    };
+    let vault = vault_state(2000, 1000);
 
     // 500 shares should be worth 1000 assets (500 * 2000 / 1000)
     assert_eq!(vault.calculate_assets(500).unwrap(), 1000);
@@ -231,17 +295,7 @@ fn test_calculate_assets_zero_shares() {
     // Test asset calculation when vault has no shares
     // Security: Math safety (Section 6)
 
-    let vault = VaultState {
-        authority: Pubkey::default(),
-        asset_mint: Pubkey::default(),
-        share_mint: Pubkey::default(),
-        total_assets: 0,
-        total_shares: 0,
-        bump: 0,
-        share_bump: 0,
-        authority_bump: 0,
-        _reserved: [0; 128],
-    };
+    let vault = vault_state(0, 0);
 
     assert_eq!(vault.calculate_assets(500).unwrap(), 0);
 }
@@ -250,143 +304,455 @@ fn test_calculate_assets_zero_shares() {
 // SECURITY TESTS - Protocol Registry (Sections 8 & 9)
 // =============================================================================
 
+fn approved_protocol(target: Pubkey, enabled: bool, invested_amount: u64) -> ApprovedProtocol {
+    ApprovedProtocol {
+        vault: Pubkey::new_unique(),
+        target,
+        expected_owner: Pubkey::new_unique(),
+        enabled,
+        invested_amount,
+        invest_cap: None,
+        target_weight_bps: 0,
+        name: "Protocol1".to_string(),
+        metadata: ProtocolMetadata {
+            kind: ProtocolKind::TokenAccount,
+            owner_program: anchor_spl::token::ID,
+            risk_tier: 0,
+            uri: String::new(),
+            uri_hash: [0; 32],
+        },
+        bump: 0,
+    }
+}
+
 #[test]
-fn test_protocol_registry_is_approved() {
-    // Test protocol registry approval logic
+fn test_approved_protocol_pda_unique_per_vault_and_target() {
+    // Test that each (vault, target) pair gets its own entry PDA
+    // Security: Account ownership validation (Section 2)
+
+    let program_id = tokenized_vault::id();
+    let vault = Pubkey::new_unique();
+    let other_vault = Pubkey::new_unique();
+    let target = Pubkey::new_unique();
+    let other_target = Pubkey::new_unique();
+
+    let (entry, _) = Pubkey::find_program_address(
+        &[PROTOCOL_SEED, vault.as_ref(), target.as_ref()],
+        &program_id,
+    );
+    let (entry_other_target, _) = Pubkey::find_program_address(
+        &[PROTOCOL_SEED, vault.as_ref(), other_target.as_ref()],
+        &program_id,
+    );
+    let (entry_other_vault, _) = Pubkey::find_program_address(
+        &[PROTOCOL_SEED, other_vault.as_ref(), target.as_ref()],
+        &program_id,
+    );
+    let (registry, _) = Pubkey::find_program_address(
+        &[b"protocol_registry", vault.as_ref()],
+        &program_id,
+    );
+
+    assert_ne!(entry, entry_other_target, "Entries must be unique per target");
+    assert_ne!(entry, entry_other_vault, "Entries must be unique per vault");
+    assert_ne!(entry, registry);
+}
+
+#[test]
+fn test_approved_protocol_track_investment() {
+    // Test investment tracking with checked math
+    // Security: Math safety (Section 6)
+
+    let mut protocol = approved_protocol(Pubkey::new_unique(), true, 1000);
+
+    protocol.track_investment(500).unwrap();
+
+    assert_eq!(protocol.invested_amount, 1500);
+}
+
+#[test]
+fn test_approved_protocol_track_investment_overflow() {
+    // Test that tracking investment with overflow fails
+    // Security: Math safety (Section 6)
+
+    let mut protocol = approved_protocol(Pubkey::new_unique(), true, u64::MAX - 100);
+
+    let result = protocol.track_investment(200);
+    assert!(result.is_err(), "Should fail on overflow");
+    assert_eq!(protocol.invested_amount, u64::MAX - 100);
+}
+
+#[test]
+fn test_approved_protocol_track_investment_respects_cap() {
+    // Test that the per-protocol invest cap is enforced
     // Security: Business logic (Section 8)
 
-    let registry = ProtocolRegistry {
-        vault: Pubkey::new_unique(),
+    let mut protocol = approved_protocol(Pubkey::new_unique(), true, 0);
+    protocol.invest_cap = Some(1000);
+
+    protocol.track_investment(600).unwrap();
+    protocol.track_investment(400).unwrap();
+    assert_eq!(protocol.invested_amount, 1000, "Investing up to the cap is allowed");
+
+    let result = protocol.track_investment(1);
+    assert!(result.is_err(), "Should fail above the cap");
+    assert_eq!(protocol.invested_amount, 1000, "Failed investment must not be tracked");
+}
+
+#[test]
+fn test_approved_protocol_track_divestment() {
+    // Test that divesting reduces the tracked amount and cannot underflow
+    // Security: Math safety (Section 6)
+
+    let mut protocol = approved_protocol(Pubkey::new_unique(), true, 1000);
+
+    protocol.track_divestment(400).unwrap();
+    assert_eq!(protocol.invested_amount, 600);
+
+    assert!(protocol.track_divestment(601).is_err(), "Should fail on underflow");
+    assert_eq!(protocol.invested_amount, 600);
+}
+
+#[test]
+fn test_strategy_report_books_gain_and_loss() {
+    // Test that adapter reports move invested_amount and total_assets together
+    // Security: Math safety (Section 6)
+
+    let mut protocol = approved_protocol(Pubkey::new_unique(), true, 1_000);
+    let mut vault = vault_state(5_000, 5_000);
+
+    // Interest accrued: the share price rises
+    let (gain, loss) = protocol.record_report(1_050);
+    assert_eq!((gain, loss), (50, 0));
+    vault.apply_report(gain, loss).unwrap();
+    assert_eq!(protocol.invested_amount, 1_050);
+    assert_eq!(vault.total_assets, 5_050);
+
+    // Loss injected by the strategy: the share price falls
+    let (gain, loss) = protocol.record_report(800);
+    assert_eq!((gain, loss), (0, 250));
+    vault.apply_report(gain, loss).unwrap();
+    assert_eq!(protocol.invested_amount, 800);
+    assert_eq!(vault.total_assets, 4_800);
+    assert_eq!(vault.calculate_assets(1_000).unwrap(), 960);
+
+    // A loss larger than the tracked assets is rejected, not wrapped
+    assert!(vault.apply_report(0, 4_801).is_err());
+    assert!(vault.apply_report(u64::MAX, 0).is_err());
+}
+
+#[test]
+fn test_recall_books_yield_above_invested_amount() {
+    // Test that a recall clears the entry and books the whole target balance
+    // Security: Business logic (Section 8)
+
+    let mut vault = vault_state(5_000, 5_000);
+
+    // Target earned yield: the excess comes back as a gain
+    let mut protocol = approved_protocol(Pubkey::new_unique(), true, 1_000);
+    let (gain, loss) = protocol.record_recall(1_200);
+    assert_eq!((gain, loss), (200, 0));
+    vault.apply_report(gain, loss).unwrap();
+    assert_eq!(protocol.invested_amount, 0);
+    assert_eq!(vault.total_assets, 5_200);
+
+    // Target lost funds: the shortfall is written off
+    let mut protocol = approved_protocol(Pubkey::new_unique(), true, 1_000);
+    let (gain, loss) = protocol.record_recall(700);
+    assert_eq!((gain, loss), (0, 300));
+    vault.apply_report(gain, loss).unwrap();
+    assert_eq!(protocol.invested_amount, 0);
+    assert_eq!(vault.total_assets, 4_900);
+
+    // A donation to a target with nothing invested is recalled as a gain
+    let mut protocol = approved_protocol(Pubkey::new_unique(), true, 0);
+    assert_eq!(protocol.record_recall(50), (50, 0));
+}
+
+#[test]
+fn test_strategy_adapter_discriminators() {
+    // Test that adapter discriminators match Anchor's sighash of the method name
+    // Security: CPI security (Section 5)
+
+    let sighash = |name: &str| -> [u8; 8] {
+        let hash = hashv(&[format!("global:{name}").as_bytes()]);
+        hash.to_bytes()[..8].try_into().unwrap()
+    };
+
+    assert_eq!(adapter::DEPOSIT_DISCRIMINATOR, sighash("deposit"));
+    assert_eq!(adapter::WITHDRAW_DISCRIMINATOR, sighash("withdraw"));
+    assert_eq!(adapter::TOTAL_VALUE_DISCRIMINATOR, sighash("total_value"));
+}
+
+#[test]
+fn test_allocation_gap_within_tolerance_band() {
+    // Test gap calculation against target weight of total assets
+    // Security: Business logic (Section 8)
+
+    let mut protocol = approved_protocol(Pubkey::new_unique(), true, 0);
+    protocol.target_weight_bps = 5_000; // 50% of 10_000 = 5_000 target
+
+    // Under by 5_000, tolerance 1% = 100
+    assert_eq!(protocol.allocation_gap(10_000, 100).unwrap(), AllocationGap::Under(5_000));
+
+    // Within band on either side
+    protocol.invested_amount = 4_950;
+    assert_eq!(protocol.allocation_gap(10_000, 100).unwrap(), AllocationGap::Balanced);
+    protocol.invested_amount = 5_100;
+    assert_eq!(protocol.allocation_gap(10_000, 100).unwrap(), AllocationGap::Balanced);
+
+    // Over by more than the band
+    protocol.invested_amount = 6_000;
+    assert_eq!(protocol.allocation_gap(10_000, 100).unwrap(), AllocationGap::Over(1_000));
+
+    // Zero weight means everything invested is excess
+    protocol.target_weight_bps = 0;
+    assert_eq!(protocol.allocation_gap(10_000, 0).unwrap(), AllocationGap::Over(6_000));
+}
+
+#[test]
+fn test_allocation_gap_max_values() {
+    // Test that u128 intermediates prevent overflow
+    // Security: Math safety (Section 6)
+
+    let mut protocol = approved_protocol(Pubkey::new_unique(), true, 0);
+    protocol.target_weight_bps = 10_000;
+
+    assert_eq!(
+        protocol.allocation_gap(u64::MAX, 0).unwrap(),
+        AllocationGap::Under(u64::MAX)
+    );
+}
+
+#[test]
+fn test_legacy_registry_converts_to_protocol_entries() {
+    // Test that inline registry entries carry over to per-protocol accounts
+    // Security: Account data validation - upgrade path
+
+    let vault = Pubkey::new_unique();
+    let target = Pubkey::new_unique();
+    let legacy = LegacyProtocolRegistry {
+        vault,
         approved_protocols: vec![
-            tokenized_vault::state::ApprovedProtocol {
-                target: Pubkey::new_unique(),
-                enabled: true,
-                invested_amount: 0,
+            LegacyApprovedProtocol {
+                target,
+                enabled: false,
+                invested_amount: 700,
                 name: "Protocol1".to_string(),
             },
-            tokenized_vault::state::ApprovedProtocol {
+            LegacyApprovedProtocol {
                 target: Pubkey::new_unique(),
-                enabled: false,
+                enabled: true,
                 invested_amount: 0,
                 name: "Protocol2".to_string(),
             },
         ],
-        bump: 0,
+        bump: 251,
     };
 
-    let protocol1_target = registry.approved_protocols[0].target;
-    let protocol2_target = registry.approved_protocols[1].target;
-    let unknown_target = Pubkey::new_unique();
-
-    assert!(registry.is_protocol_approved(&protocol1_target));
-    assert!(!registry.is_protocol_approved(&protocol2_target)); // Disabled
-    assert!(!registry.is_protocol_approved(&unknown_target)); // Not in registry
+    let mut data = ProtocolRegistry::DISCRIMINATOR.to_vec();
+    legacy.serialize(&mut data).unwrap();
+    data.resize(LEGACY_PROTOCOL_REGISTRY_SPACE, 0);
+    assert_eq!(LegacyProtocolRegistry::try_from_account_data(&data).unwrap(), legacy);
+
+    // Current-size registries are never read as legacy ones
+    assert!(LegacyProtocolRegistry::try_from_account_data(&data[..ProtocolRegistry::SPACE]).is_err());
+
+    let registry = ProtocolRegistry::from_legacy(&legacy).unwrap();
+    assert_eq!(registry.vault, vault);
+    assert_eq!(registry.protocol_count, 2);
+    assert_eq!(registry.bump, 251);
+    assert_eq!(registry.version, PROTOCOL_REGISTRY_VERSION);
+
+    let owner = Pubkey::new_unique();
+    let protocol = ApprovedProtocol::from_legacy(vault, &legacy.approved_protocols[0], owner, anchor_spl::token::ID, 7);
+    assert_eq!(protocol.target, target);
+    assert_eq!(protocol.expected_owner, owner);
+    assert!(!protocol.enabled, "Disabled entries stay disabled");
+    assert_eq!(protocol.invested_amount, 700, "Invested amounts carry over");
+    assert_eq!(protocol.invest_cap, None);
+
+    let mut entry = Vec::new();
+    protocol.try_serialize(&mut entry).unwrap();
+    assert!(entry.len() <= ApprovedProtocol::SPACE);
 }
 
 #[test]
-fn test_protocol_registry_get_protocol_mut() {
-    // Test getting mutable protocol reference
+fn test_registry_total_weight_capped_at_100_percent() {
+    // Test that target weights across protocols cannot exceed 100%
     // Security: Business logic (Section 8)
 
     let mut registry = ProtocolRegistry {
         vault: Pubkey::new_unique(),
-        approved_protocols: vec![
-            tokenized_vault::state::ApprovedProtocol {
-                target: Pubkey::new_unique(),
-                enabled: true,
-                invested_amount: 1000,
-                name: "Protocol1".to_string(),
-            },
-        ],
+        protocol_count: 2,
+        total_target_weight_bps: 0,
         bump: 0,
+        version: PROTOCOL_REGISTRY_VERSION,
     };
 
-    let target = registry.approved_protocols[0].target;
+    registry.update_total_weight(0, 6_000).unwrap();
+    registry.update_total_weight(0, 4_000).unwrap();
+    assert_eq!(registry.total_target_weight_bps, 10_000);
 
-    let protocol = registry.get_protocol_mut(&target);
-    assert!(protocol.is_some());
-    assert_eq!(protocol.unwrap().invested_amount, 1000);
+    assert!(registry.update_total_weight(0, 1).is_err(), "Should reject > 100%");
+    assert_eq!(registry.total_target_weight_bps, 10_000);
 
-    // Test non-existent protocol
-    let unknown_target = Pubkey::new_unique();
-    assert!(registry.get_protocol_mut(&unknown_target).is_none());
+    // Lowering one weight frees room for another
+    registry.update_total_weight(6_000, 5_000).unwrap();
+    registry.update_total_weight(4_000, 5_000).unwrap();
+    assert_eq!(registry.total_target_weight_bps, 10_000);
+
+    // Removing a protocol drops its weight
+    registry.update_total_weight(5_000, 0).unwrap();
+    assert_eq!(registry.total_target_weight_bps, 5_000);
 }
 
 #[test]
-fn test_protocol_registry_track_investment() {
-    // Test investment tracking with checked math
-    // Security: Math safety (Section 6)
+fn test_approved_protocol_space_fits_max_name_and_metadata() {
+    // Test that the entry fits a maximum-length name and metadata URI
+    // Security: Business logic (Section 8)
 
-    let mut registry = ProtocolRegistry {
-        vault: Pubkey::new_unique(),
-        approved_protocols: vec![
-            tokenized_vault::state::ApprovedProtocol {
-                target: Pubkey::new_unique(),
-                enabled: true,
-                invested_amount: 1000,
-                name: "Protocol1".to_string(),
-            },
-        ],
-        bump: 0,
+    let mut protocol = approved_protocol(Pubkey::new_unique(), true, u64::MAX);
+    protocol.invest_cap = Some(u64::MAX);
+    protocol.name = "x".repeat(32);
+    protocol.metadata = ProtocolMetadata {
+        kind: ProtocolKind::Lending,
+        owner_program: Pubkey::new_unique(),
+        risk_tier: u8::MAX,
+        uri: "u".repeat(MAX_PROTOCOL_URI_LEN),
+        uri_hash: [0xff; 32],
     };
 
-    let target = registry.approved_protocols[0].target;
+    let serialized = protocol.try_to_vec().unwrap();
+    assert!(8 + serialized.len() <= ApprovedProtocol::SPACE);
+}
+
+#[test]
+fn test_config_apply_updates_creation_fee() {
+    // Test that update_config parameters replace the fee recipient and creation fee
+    // Security: Business logic (Section 8)
 
-    registry.track_investment(&target, 500).unwrap();
+    let admin = Pubkey::new_unique();
+    let mut config = GlobalConfig {
+        admin,
+        fee_recipient: Pubkey::default(),
+        creation_fee_lamports: 0,
+        vault_count: 7,
+        bump: 0,
+        _reserved: [0; 64],
+    };
+    let params = ConfigParams {
+        fee_recipient: Pubkey::new_unique(),
+        creation_fee_lamports: 1_000_000,
+    };
 
-    assert_eq!(registry.approved_protocols[0].invested_amount, 1500);
+    config.apply(&params);
+    assert_eq!(config.fee_recipient, params.fee_recipient);
+    assert_eq!(config.creation_fee_lamports, 1_000_000);
+    assert_eq!(config.admin, admin, "Admin is only changed by update_config");
+    assert_eq!(config.vault_count, 7, "Parameters never touch the vault index");
 }
 
 #[test]
-fn test_protocol_registry_track_investment_overflow() {
-    // Test that tracking investment with overflow fails
+fn test_config_vault_index_is_contiguous() {
+    // Test that each created vault gets the next index and the count cannot overflow
     // Security: Math safety (Section 6)
 
-    let mut registry = ProtocolRegistry {
-        vault: Pubkey::new_unique(),
-        approved_protocols: vec![
-            tokenized_vault::state::ApprovedProtocol {
-                target: Pubkey::new_unique(),
-                enabled: true,
-                invested_amount: u64::MAX - 100,
-                name: "Protocol1".to_string(),
-            },
-        ],
+    let mut config = GlobalConfig {
+        admin: Pubkey::new_unique(),
+        fee_recipient: Pubkey::default(),
+        creation_fee_lamports: 0,
+        vault_count: 0,
         bump: 0,
+        _reserved: [0; 64],
     };
 
-    let target = registry.approved_protocols[0].target;
+    assert_eq!(config.next_vault_index().unwrap(), 0);
+    assert_eq!(config.next_vault_index().unwrap(), 1);
+    assert_eq!(config.vault_count, 2);
 
-    let result = registry.track_investment(&target, 200);
-    assert!(result.is_err(), "Should fail on overflow");
+    config.vault_count = u64::MAX;
+    assert!(config.next_vault_index().is_err());
+
+    // Index entry PDAs are distinct per index
+    let program_id = tokenized_vault::id();
+    let (entry_0, _) =
+        Pubkey::find_program_address(&[VAULT_INDEX_SEED, &0u64.to_le_bytes()], &program_id);
+    let (entry_1, _) =
+        Pubkey::find_program_address(&[VAULT_INDEX_SEED, &1u64.to_le_bytes()], &program_id);
+    assert_ne!(entry_0, entry_1);
 }
 
 #[test]
-fn test_protocol_registry_track_investment_unknown_protocol() {
-    // Test tracking investment for non-existent protocol
+fn test_config_accounts_space() {
+    // Test that the config, allowlist and index accounts fit their allocated space
     // Security: Business logic (Section 8)
 
-    let mut registry = ProtocolRegistry {
+    let config = GlobalConfig {
+        admin: Pubkey::new_unique(),
+        fee_recipient: Pubkey::new_unique(),
+        creation_fee_lamports: u64::MAX,
+        vault_count: u64::MAX,
+        bump: u8::MAX,
+        _reserved: [0; 64],
+    };
+    assert_eq!(8 + config.try_to_vec().unwrap().len(), GlobalConfig::SPACE);
+
+    let allowed_mint = AllowedMint {
+        mint: Pubkey::new_unique(),
+        allowed: true,
+        bump: u8::MAX,
+    };
+    assert_eq!(8 + allowed_mint.try_to_vec().unwrap().len(), AllowedMint::SPACE);
+
+    let entry = VaultIndexEntry {
+        index: u64::MAX,
         vault: Pubkey::new_unique(),
-        approved_protocols: vec![
-            tokenized_vault::state::ApprovedProtocol {
-                target: Pubkey::new_unique(),
-                enabled: true,
-                invested_amount: 1000,
-                name: "Protocol1".to_string(),
-            },
-        ],
-        bump: 0,
+        asset_mint: Pubkey::new_unique(),
+        vault_id: u64::MAX,
+        authority: Pubkey::new_unique(),
+        created_at: i64::MAX,
+        bump: u8::MAX,
     };
+    assert_eq!(8 + entry.try_to_vec().unwrap().len(), VaultIndexEntry::SPACE);
+}
 
-    let unknown_target = Pubkey::new_unique();
+#[test]
+fn test_share_metadata_length_limits() {
+    // Test that share metadata fields are bounded
+    // Security: Business logic (Section 8)
 
-    // Should succeed but not update anything (no protocol found)
-    let result = registry.track_investment(&unknown_target, 500);
-    assert!(result.is_ok());
+    let mut metadata = ShareMetadata {
+        name: "n".repeat(MAX_SHARE_NAME_LEN),
+        symbol: "s".repeat(MAX_SHARE_SYMBOL_LEN),
+        uri: "u".repeat(MAX_SHARE_URI_LEN),
+    };
+    assert!(metadata.validate().is_ok());
 
-    // Original protocol amount should be unchanged
-    assert_eq!(registry.approved_protocols[0].invested_amount, 1000);
+    metadata.symbol.push('s');
+    assert!(metadata.validate().is_err(), "Should reject long symbol");
+}
+
+#[test]
+fn test_share_metadata_tlv_size_grows_with_fields() {
+    // Test that the metadata TLV size used for rent tracks field lengths
+    // Security: Business logic (Section 8)
+
+    let mint = Pubkey::new_unique();
+    let update_authority = Pubkey::new_unique();
+    let short = ShareMetadata {
+        name: "Vault Shares".to_string(),
+        symbol: "vTKN".to_string(),
+        uri: String::new(),
+    };
+    let long = ShareMetadata {
+        uri: "u".repeat(MAX_SHARE_URI_LEN),
+        ..short.clone()
+    };
+
+    let short_size = short.tlv_size_of(&mint, &update_authority).unwrap();
+    let long_size = long.tlv_size_of(&mint, &update_authority).unwrap();
+    assert_eq!(long_size - short_size, MAX_SHARE_URI_LEN);
 }
 
 // =============================================================================
@@ -398,17 +764,7 @@ fn test_deposit_first_deposit_1_to_1_logic() {
     // Test that first deposit mints 1:1 shares
     // Security: Math safety (Section 6)
 
-    let vault = VaultState {
-        authority: Pubkey::default(),
-        asset_mint: Pubkey::default(),
-        share_mint: Pubkey::default(),
-        total_assets: 0,
-        total_shares: 0,
-        bump: 0,
-        share_bump: 0,
-        authority_bump: 0,
-        _reserved: [0; 128],
-    };
+    let vault = vault_state(0, 0);
 
     // Test various amounts
     assert_eq!(vault.calculate_shares(1000).unwrap(), 1000);
@@ -431,17 +787,7 @@ fn test_deposit_after_profit_logic() {
     // Test that shares are calculated correctly when vault has profits
     // Security: Math safety and business logic (Sections 6 & 8)
 
-    let vault = VaultState {
-        authority: Pubkey::default(),
-        asset_mint: Pubkey::default(),
-        share_mint: Pubkey::default(),
-        total_assets: 2000,
-        total_shares: 1000,
-        bump: 0,
-        share_bump: 0,
-        authority_bump: 0,
-        _reserved: [0; 128],
-    };
+    let vault = vault_state(2000, 1000);
 
     // Deposit 100 assets should mint 50 shares (100 * 1000 / 2000)
     let deposit_amount = 100u64;
@@ -450,21 +796,412 @@ fn test_deposit_after_profit_logic() {
 }
 
 #[test]
-fn test_full_deposit_flow_logic() {
-    // End-to-end test validating deposit flow logic
-    // Security: Validates CEI pattern implementation
+fn test_deposit_cap_limits_total_assets() {
+    // Test that deposits stop at the cap and max_deposit reports the headroom
+    // Security: Business logic (Section 8)
+
+    let mut vault = vault_state(900, 900);
+
+    // Uncapped
+    assert_eq!(vault.max_deposit(), u64::MAX);
+    assert!(vault.check_deposit_cap(u64::MAX - 900).is_ok());
+
+    vault.deposit_cap = 1_000;
+    assert_eq!(vault.max_deposit(), 100);
+    assert!(vault.check_deposit_cap(100).is_ok(), "Filling the cap exactly is allowed");
+    assert!(vault.check_deposit_cap(101).is_err());
+
+    // Lowering the cap below total_assets leaves no headroom
+    vault.deposit_cap = 500;
+    assert_eq!(vault.max_deposit(), 0);
+    assert!(vault.check_deposit_cap(1).is_err());
+
+    // Overflow is rejected rather than wrapping under the cap
+    vault.deposit_cap = u64::MAX;
+    assert!(vault.check_deposit_cap(u64::MAX).is_err());
+}
+
+#[test]
+fn test_user_position_tracks_deposits_and_cap() {
+    // Test that a user's cumulative deposits are tracked and capped
+    // Security: Business logic (Section 8)
+
+    let vault = Pubkey::new_unique();
+    let user = Pubkey::new_unique();
+    let mut position = UserPosition {
+        vault: Pubkey::default(),
+        user: Pubkey::default(),
+        total_deposited: 0,
+        total_shares_minted: 0,
+        first_deposit_at: 0,
+        last_deposit_at: 0,
+        bump: 0,
+        unlock_at: 0,
+        locked_shares: 0,
+        _reserved: [0; 48],
+    };
+
+    let cap = 1_000u64;
+    position.check_deposit_cap(cap, 600).unwrap();
+    position.record_deposit(vault, user, 254, 600, 600, 100, 0).unwrap();
+    assert_eq!(position.vault, vault);
+    assert_eq!(position.user, user);
+    assert_eq!(position.bump, 254);
+    assert_eq!(position.first_deposit_at, 100);
+
+    position.check_deposit_cap(cap, 400).unwrap();
+    position.record_deposit(vault, user, 254, 400, 380, 200, 0).unwrap();
+    assert_eq!(position.total_deposited, 1_000);
+    assert_eq!(position.total_shares_minted, 980);
+    assert_eq!(position.first_deposit_at, 100, "First deposit time is kept");
+    assert_eq!(position.last_deposit_at, 200);
+
+    assert!(position.check_deposit_cap(cap, 1).is_err(), "Cap reached");
+    assert!(position.check_deposit_cap(0, u64::MAX - 1_000).is_ok(), "0 = uncapped");
+    assert!(position.record_deposit(vault, user, 254, u64::MAX, 0, 300, 0).is_err());
+
+    let serialized = position.try_to_vec().unwrap();
+    assert_eq!(8 + serialized.len(), UserPosition::SPACE);
+}
+
+#[test]
+fn test_deposit_lockup_blocks_early_redemption() {
+    // Test that each deposit locks the position for the full lockup
+    // Security: Business logic (Section 8)
+
+    let vault = Pubkey::new_unique();
+    let user = Pubkey::new_unique();
+    let mut position = UserPosition {
+        vault: Pubkey::default(),
+        user: Pubkey::default(),
+        total_deposited: 0,
+        total_shares_minted: 0,
+        first_deposit_at: 0,
+        last_deposit_at: 0,
+        bump: 0,
+        unlock_at: 0,
+        locked_shares: 0,
+        _reserved: [0; 48],
+    };
+
+    // Holders who never deposited (e.g. received shares) are unlocked
+    position.check_unlocked(0).unwrap();
+
+    let day = 24 * 60 * 60;
+    position.record_deposit(vault, user, 254, 100, 100, 1_000, day).unwrap();
+    assert_eq!(position.unlock_at, 1_000 + day);
+    assert_eq!(position.locked_shares, 100);
+    assert!(position.check_unlocked(1_000 + day - 1).is_err(), "Still locked");
+    position.check_unlocked(1_000 + day).unwrap();
+
+    // A top-up relocks the whole position
+    position.record_deposit(vault, user, 254, 100, 100, 5_000, day).unwrap();
+    assert_eq!(position.unlock_at, 5_000 + day);
+    assert_eq!(position.locked_shares, 200);
+
+    // Shortening the lockup never releases an existing lock early
+    position.record_deposit(vault, user, 254, 100, 100, 6_000, 0).unwrap();
+    assert_eq!(position.unlock_at, 5_000 + day);
+    assert_eq!(position.locked_shares, 200, "Shares minted without a lockup stay liquid");
+    assert!(position.check_unlocked(6_000).is_err());
+}
+
+#[test]
+fn test_redeem_batch_settlement() {
+    // Test that a fulfilled batch burns escrowed shares at one rate and
+    // reserves its assets for claims
+    // Security: Business logic (Section 8)
 
     let mut vault = VaultState {
-        authority: Pubkey::default(),
-        asset_mint: Pubkey::default(),
-        share_mint: Pubkey::default(),
-        total_assets: 0,
+        pending_redeem_shares: 300,
+        ..vault_state(2_000, 1_000)
+    };
+
+    // Two requests priced at the same pre-batch rate (2 assets per share)
+    let first = vault.calculate_assets(100).unwrap();
+    let second = vault.calculate_assets(200).unwrap();
+    assert_eq!((first, second), (200, 400));
+
+    vault.settle_redeems(300, first + second).unwrap();
+    assert_eq!(vault.total_assets, 1_400);
+    assert_eq!(vault.total_shares, 700);
+    assert_eq!(vault.pending_redeem_shares, 0);
+    assert_eq!(vault.claimable_assets, 600);
+
+    // The rate for remaining holders is unchanged
+    assert_eq!(vault.calculate_assets(700).unwrap(), 1_400);
+
+    // Reserved assets are not idle
+    assert_eq!(vault.idle_assets(1_000), 400);
+    assert_eq!(vault.idle_assets(500), 0);
+
+    // Settling more shares than are pending fails
+    assert!(vault.settle_redeems(1, 0).is_err());
+
+    let request = RedeemRequest {
+        vault: Pubkey::new_unique(),
+        user: Pubkey::new_unique(),
+        shares: 100,
+        assets: 0,
+        requested_at: 1,
+        fulfilled_at: 0,
+        bump: 255,
+    };
+    assert!(!request.is_fulfilled());
+    let serialized = request.try_to_vec().unwrap();
+    assert_eq!(8 + serialized.len(), RedeemRequest::SPACE);
+}
+
+#[test]
+fn test_last_redeem_batch_dust_does_not_block_close() {
+    // Test that rounding dust left by the batch redeeming the last shares
+    // does not keep the vault from closing
+    // Security: Business logic (Section 8)
+
+    let mut vault = VaultState {
+        pending_redeem_shares: 3,
+        ..vault_state(10, 3)
+    };
+    assert!(!vault.is_empty());
+
+    // Each request rounds down against the same pre-batch rate
+    let first = vault.calculate_assets(1).unwrap();
+    let second = vault.calculate_assets(2).unwrap();
+    assert_eq!((first, second), (3, 6));
+
+    vault.settle_redeems(3, first + second).unwrap();
+    assert_eq!(vault.total_shares, 0);
+    assert_eq!(vault.total_assets, 1, "One unit no share can claim");
+
+    // Still owed to claimants until they claim
+    assert!(!vault.is_empty());
+    vault.claimable_assets = 0;
+    assert!(vault.is_empty());
+
+    // Outstanding shares or pending redeems keep the vault open
+    assert!(!VaultState { total_shares: 1, ..vault.clone() }.is_empty());
+    assert!(!VaultState { pending_redeem_shares: 1, ..vault }.is_empty());
+}
+
+#[test]
+fn test_min_idle_buffer_limits_invest() {
+    // Test that invest keeps min_idle_bps of total_assets in the vault
+    // Security: Business logic (Section 8)
+
+    let mut vault = vault_state(1_000, 1_000);
+
+    // No buffer: the whole idle balance can be invested
+    assert_eq!(vault.max_invest(1_000).unwrap(), 1_000);
+    vault.check_idle_buffer(1_000, 1_000).unwrap();
+
+    // 10% buffer keeps 100 idle
+    vault.min_idle_bps = 1_000;
+    assert_eq!(vault.min_idle_assets().unwrap(), 100);
+    assert_eq!(vault.max_invest(1_000).unwrap(), 900);
+    vault.check_idle_buffer(1_000, 900).unwrap();
+    assert!(vault.check_idle_buffer(1_000, 901).is_err());
+
+    // Rounds the buffer up, in favor of liquidity
+    vault.total_assets = 1_001;
+    assert_eq!(vault.min_idle_assets().unwrap(), 101);
+
+    // Already below the buffer: nothing can be invested
+    assert_eq!(vault.max_invest(50).unwrap(), 0);
+
+    // Assets reserved for redeem claims don't count as idle
+    vault.claimable_assets = 200;
+    assert_eq!(vault.max_invest(1_001).unwrap(), 700);
+}
+
+#[test]
+fn test_referral_stats_accumulate_volume() {
+    // Test that referred deposits accumulate per referrer
+    // Security: Business logic (Section 8)
+
+    let vault = Pubkey::new_unique();
+    let referrer = Pubkey::new_unique();
+    let mut stats = ReferralStats {
+        vault: Pubkey::default(),
+        referrer: Pubkey::default(),
+        total_deposited: 0,
         total_shares: 0,
+        deposit_count: 0,
         bump: 0,
-        share_bump: 0,
-        authority_bump: 0,
-        _reserved: [0; 128],
+        _reserved: [0; 32],
+    };
+
+    stats.record_deposit(vault, referrer, 253, 1_000, 1_000).unwrap();
+    stats.record_deposit(vault, referrer, 253, 500, 450).unwrap();
+    assert_eq!(stats.vault, vault);
+    assert_eq!(stats.referrer, referrer);
+    assert_eq!(stats.bump, 253);
+    assert_eq!(stats.total_deposited, 1_500);
+    assert_eq!(stats.total_shares, 1_450);
+    assert_eq!(stats.deposit_count, 2);
+
+    assert!(stats.record_deposit(vault, referrer, 253, u64::MAX, 0).is_err());
+
+    let serialized = stats.try_to_vec().unwrap();
+    assert_eq!(8 + serialized.len(), ReferralStats::SPACE);
+}
+
+#[test]
+fn test_shutdown_blocks_inflows_and_waives_lockup() {
+    // Test that a shut-down vault rejects deposits/investments but lets
+    // locked holders exit
+    // Security: Business logic (Section 8)
+
+    let mut vault = vault_state(1_000, 1_000);
+    let mut position = UserPosition {
+        vault: Pubkey::default(),
+        user: Pubkey::default(),
+        total_deposited: 0,
+        total_shares_minted: 0,
+        first_deposit_at: 0,
+        last_deposit_at: 0,
+        bump: 0,
+        unlock_at: 0,
+        locked_shares: 0,
+        _reserved: [0; 48],
     };
+    position
+        .record_deposit(Pubkey::new_unique(), Pubkey::new_unique(), 254, 100, 100, 1_000, 3_600)
+        .unwrap();
+
+    vault.check_active().unwrap();
+    assert!(vault.check_unlockable(&position, 2_000).is_err(), "Locked while active");
+
+    vault.is_shutdown = true;
+    vault.shutdown_reason = 2;
+    assert!(vault.check_active().is_err(), "No deposits or investments");
+    vault.check_unlockable(&position, 2_000).unwrap();
+
+    // Redemptions keep the current price per share
+    assert_eq!(vault.calculate_assets(100).unwrap(), 100);
+}
+
+#[test]
+fn test_shutdown_zeroes_deposit_and_invest_limits() {
+    // Test that the maxDeposit / max_invest views report nothing once the
+    // vault is shut down, whatever the caps and idle balance
+    // Security: Business logic (Section 8)
+
+    let mut vault = vault_state(1_000, 1_000);
+    vault.deposit_cap = 5_000;
+    assert_eq!(vault.max_deposit(), 4_000);
+    assert_eq!(vault.max_invest(1_000).unwrap(), 1_000);
+
+    vault.is_shutdown = true;
+    assert_eq!(vault.max_deposit(), 0);
+    assert_eq!(vault.max_invest(1_000).unwrap(), 0);
+
+    // Uncapped vaults too
+    vault.deposit_cap = 0;
+    assert_eq!(vault.max_deposit(), 0);
+}
+
+/// Internal Merkle node, matching the program's sorted-pair hashing
+fn merkle_parent(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[&[0x01], first.as_ref(), second.as_ref()]).to_bytes()
+}
+
+#[test]
+fn test_depositor_merkle_proof_verification() {
+    // Test that only depositors in the allowlist tree can prove membership
+    // Security: Access control (Section 3)
+
+    let users: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+    let leaves: Vec<[u8; 32]> = users.iter().map(depositor_leaf).collect();
+    let left = merkle_parent(leaves[0], leaves[1]);
+    let right = merkle_parent(leaves[2], leaves[3]);
+    let root = merkle_parent(left, right);
+
+    // Every member proves membership with its sibling path
+    assert!(verify_merkle_proof(&root, leaves[0], &[leaves[1], right]));
+    assert!(verify_merkle_proof(&root, leaves[3], &[leaves[2], left]));
+
+    // Outsiders and wrong paths fail
+    let outsider = depositor_leaf(&Pubkey::new_unique());
+    assert!(!verify_merkle_proof(&root, outsider, &[leaves[1], right]));
+    assert!(!verify_merkle_proof(&root, leaves[0], &[leaves[2], right]));
+}
+
+#[test]
+fn test_vault_depositor_allowlist_gate() {
+    // Test that deposits only require a proof once a root is set
+    // Security: Access control (Section 3)
+
+    let allowed = Pubkey::new_unique();
+    let other = Pubkey::new_unique();
+    let sibling = depositor_leaf(&other);
+
+    let mut vault = vault_state(0, 0);
+
+    // No root: open to everyone without a proof
+    assert!(vault.check_depositor(&Pubkey::new_unique(), &[]).is_ok());
+
+    vault.depositor_merkle_root = merkle_parent(depositor_leaf(&allowed), sibling);
+    assert!(vault.check_depositor(&allowed, &[sibling]).is_ok());
+    assert!(vault.check_depositor(&allowed, &[]).is_err(), "Proof required");
+    assert!(vault.check_depositor(&Pubkey::new_unique(), &[sibling]).is_err());
+
+    // Oversized proofs are rejected before hashing
+    let long_proof = vec![sibling; MAX_MERKLE_PROOF_LEN + 1];
+    assert!(vault.check_depositor(&allowed, &long_proof).is_err());
+}
+
+#[test]
+fn test_deposit_with_transfer_fee_logic() {
+    // Test that shares are minted only for assets the vault received
+    // Security: Business logic (Section 8) - Token-2022 transfer fees
+
+    let mut vault = vault_state(1000, 1000);
+
+    // User sends 100, a 1% transfer fee leaves 99 in the vault token account
+    let amount_sent = 100u64;
+    let balance_before = 1000u64;
+    let balance_after = 1099u64;
+    let assets_received = balance_after.checked_sub(balance_before).unwrap();
+
+    let shares_to_mint = vault.calculate_shares(assets_received).unwrap();
+    vault.total_assets = vault.total_assets.checked_add(assets_received).unwrap();
+    vault.total_shares = vault.total_shares.checked_add(shares_to_mint).unwrap();
+
+    assert!(assets_received < amount_sent);
+    assert_eq!(shares_to_mint, 99, "Shares follow assets received, not assets sent");
+    assert_eq!(vault.total_assets, 1099, "Total assets must match the vault balance");
+}
+
+#[test]
+fn test_withdraw_sol_round_trip_logic() {
+    // Test that redeeming freshly minted shares never returns more than was deposited
+    // Security: Math safety (Section 6) - rounding favors the vault
+
+    let mut vault = vault_state(3_000_000_001, 2_000_000_000);
+
+    // deposit_sol: 1 SOL in lamports
+    let lamports = 1_000_000_000u64;
+    let shares = vault.calculate_shares(lamports).unwrap();
+    vault.total_assets = vault.total_assets.checked_add(lamports).unwrap();
+    vault.total_shares = vault.total_shares.checked_add(shares).unwrap();
+
+    // withdraw_sol: redeem the same shares
+    let assets = vault.calculate_assets(shares).unwrap();
+    vault.total_assets = vault.total_assets.checked_sub(assets).unwrap();
+    vault.total_shares = vault.total_shares.checked_sub(shares).unwrap();
+
+    assert!(assets <= lamports, "Round trip must not create lamports");
+    assert_eq!(vault.total_shares, 2_000_000_000);
+    assert!(vault.total_assets >= 3_000_000_001);
+}
+
+#[test]
+fn test_full_deposit_flow_logic() {
+    // End-to-end test validating deposit flow logic
+    // Security: Validates CEI pattern implementation
+
+    let mut vault = vault_state(0, 0);
 
     // Simulate deposit
     let deposit_amount = 1000u64;
@@ -485,47 +1222,23 @@ fn test_full_invest_flow_logic() {
     // End-to-end test validating invest flow logic
     // Security: Protocol whitelist and investment tracking
 
-    let vault = VaultState {
-        authority: Pubkey::default(),
-        asset_mint: Pubkey::default(),
-        share_mint: Pubkey::default(),
-        total_assets: 5000,
-        total_shares: 5000,
-        bump: 0,
-        share_bump: 0,
-        authority_bump: 0,
-        _reserved: [0; 128],
-    };
-
-    let mut registry = ProtocolRegistry {
-        vault: Pubkey::new_unique(),
-        approved_protocols: vec![
-            tokenized_vault::state::ApprovedProtocol {
-                target: Pubkey::new_unique(),
-                enabled: true,
-                invested_amount: 0,
-                name: "TestProtocol".to_string(),
-            },
-        ],
-        bump: 0,
-    };
+    let vault = vault_state(5000, 5000);
 
-    let protocol_target = registry.approved_protocols[0].target;
+    let mut protocol = approved_protocol(Pubkey::new_unique(), true, 0);
 
     // Verify protocol is approved and enabled
-    assert!(registry.is_protocol_approved(&protocol_target), 
-            "Protocol should be approved and enabled");
+    assert!(protocol.enabled, "Protocol should be approved and enabled");
 
     // Simulate investment
     let invest_amount = 1000u64;
-    registry.track_investment(&protocol_target, invest_amount).unwrap();
+    protocol.track_investment(invest_amount).unwrap();
 
     // Verify investment tracked correctly
-    assert_eq!(registry.approved_protocols[0].invested_amount, invest_amount);
+    assert_eq!(protocol.invested_amount, invest_amount);
     
     // Track additional investment
-    registry.track_investment(&protocol_target, 500).unwrap();
-    assert_eq!(registry.approved_protocols[0].invested_amount, 1500);
+    protocol.track_investment(500).unwrap();
+    assert_eq!(protocol.invested_amount, 1500);
     
     // Verify total assets hasn't changed (just tracking, actual transfer happens in CPI)
     assert_eq!(vault.total_assets, 5000);
@@ -547,12 +1260,10 @@ fn test_signer_validation_enforced_by_anchor() {
         authority: Pubkey::new_unique(),
         asset_mint: Pubkey::new_unique(),
         share_mint: Pubkey::new_unique(),
-        total_assets: 0,
-        total_shares: 0,
         bump: 255,
         share_bump: 254,
         authority_bump: 253,
-        _reserved: [0; 128],
+        ..vault_state(0, 0)
     };
     
     // Authority must be set and valid
@@ -576,24 +1287,14 @@ fn test_authority_validation_enforced_by_anchor() {
         authority: authority1,
         asset_mint: Pubkey::new_unique(),
         share_mint: Pubkey::new_unique(),
-        total_assets: 1000,
-        total_shares: 1000,
-        bump: 0,
-        share_bump: 0,
-        authority_bump: 0,
-        _reserved: [0; 128],
+        ..vault_state(1000, 1000)
     };
     
     let vault2 = VaultState {
         authority: authority2,
         asset_mint: Pubkey::new_unique(),
         share_mint: Pubkey::new_unique(),
-        total_assets: 2000,
-        total_shares: 2000,
-        bump: 0,
-        share_bump: 0,
-        authority_bump: 0,
-        _reserved: [0; 128],
+        ..vault_state(2000, 2000)
     };
     
     // Verify that different vaults have different authorities
@@ -654,17 +1355,7 @@ fn test_zero_amount_validation() {
     assert!(valid_amount > 0, "Valid amount should pass");
     
     // Test with vault operations
-    let vault = VaultState {
-        authority: Pubkey::default(),
-        asset_mint: Pubkey::default(),
-        share_mint: Pubkey::default(),
-        total_assets: 0,
-        total_shares: 0,
-        bump: 0,
-        share_bump: 0,
-        authority_bump: 0,
-        _reserved: [0; 128],
-    };
+    let vault = vault_state(0, 0);
     
     // Valid amounts should work
     assert!(vault.calculate_shares(100).is_ok());
@@ -681,17 +1372,7 @@ fn test_reentrancy_protection_pattern() {
     //
     // Test that state updates happen before operations
     
-    let mut vault = VaultState {
-        authority: Pubkey::default(),
-        asset_mint: Pubkey::default(),
-        share_mint: Pubkey::default(),
-        total_assets: 1000,
-        total_shares: 500,  // 2:1 ratio to make the difference clear
-        bump: 0,
-        share_bump: 0,
-        authority_bump: 0,
-        _reserved: [0; 128],
-    };
+    let mut vault = vault_state(1000, 500); // 2:1 ratio to make the difference clear
     
     let initial_assets = vault.total_assets;
     let initial_shares = vault.total_shares;
@@ -738,50 +1419,31 @@ fn test_protocol_whitelist_enforcement() {
     //
     // Test that protocol whitelist properly enforces enabled/disabled status
     
+    let program_id = tokenized_vault::id();
+    let vault = Pubkey::new_unique();
     let enabled_target = Pubkey::new_unique();
     let disabled_target = Pubkey::new_unique();
     let unknown_target = Pubkey::new_unique();
-    
-    let registry = ProtocolRegistry {
-        vault: Pubkey::new_unique(),
-        approved_protocols: vec![
-            tokenized_vault::state::ApprovedProtocol {
-                target: enabled_target,
-                enabled: true,
-                invested_amount: 0,
-                name: "EnabledProtocol".to_string(),
-            },
-            tokenized_vault::state::ApprovedProtocol {
-                target: disabled_target,
-                enabled: false,
-                invested_amount: 0,
-                name: "DisabledProtocol".to_string(),
-            },
-        ],
-        bump: 0,
-    };
+
+    let enabled = approved_protocol(enabled_target, true, 0);
+    let disabled = approved_protocol(disabled_target, false, 0);
 
     // Enabled protocol should be approved
-    assert!(registry.is_protocol_approved(&enabled_target), 
-            "Enabled protocol must be approved");
+    assert!(enabled.enabled, "Enabled protocol must be approved");
     
     // Disabled protocol should NOT be approved
-    assert!(!registry.is_protocol_approved(&disabled_target), 
-            "Disabled protocol must NOT be approved");
+    assert!(!disabled.enabled, "Disabled protocol must NOT be approved");
     
-    // Unknown protocol should NOT be approved
-    assert!(!registry.is_protocol_approved(&unknown_target),
-            "Unknown protocol must NOT be approved");
-    
-    // Test that we can find both protocols in the list
-    assert!(registry.approved_protocols.iter().any(|p| p.target == enabled_target));
-    assert!(registry.approved_protocols.iter().any(|p| p.target == disabled_target));
-    
-    // But only enabled one is approved
-    let approved_count = registry.approved_protocols.iter()
-        .filter(|p| p.enabled && registry.is_protocol_approved(&p.target))
-        .count();
-    assert_eq!(approved_count, 1, "Only one protocol should be approved");
+    // Unknown protocol has no entry: its seeds derive an address that was never initialized
+    let entry_for = |target: &Pubkey| {
+        Pubkey::find_program_address(
+            &[PROTOCOL_SEED, vault.as_ref(), target.as_ref()],
+            &program_id,
+        )
+        .0
+    };
+    assert_ne!(entry_for(&unknown_target), entry_for(&enabled_target));
+    assert_ne!(entry_for(&unknown_target), entry_for(&disabled_target));
 }
 
 // =============================================================================
@@ -799,7 +1461,7 @@ fn test_protocol_whitelist_enforcement() {
 // above provide comprehensive coverage of:
 // -  PDA validation and uniqueness
 // -  Math safety with overflow protection  
-// -  Protocol registry logic and whitelist enforcement
+// -  Protocol entry logic and whitelist enforcement
 // -  ERC-4626 share calculation correctness
 // -  Authority and access control (enforced by Anchor's type system)
 // -  Token account validation (enforced by Anchor constraints)
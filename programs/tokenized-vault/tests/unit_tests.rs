@@ -1,3 +1,9 @@
+#![allow(
+    unused_comparisons,
+    clippy::absurd_extreme_comparisons,
+    clippy::inconsistent_digit_grouping
+)]
+
 use anchor_lang::prelude::*;
 
 #[cfg(test)]